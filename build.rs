@@ -0,0 +1,86 @@
+//! Validates `content/projects.toml` and converts it to JSON for the Projects
+//! window to `include_str!`, so a bad entry fails the build instead of showing
+//! up broken on the site.
+
+use std::env;
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+const PROJECTS: &str = "content/projects.toml";
+const SPLASHES_DIR: &str = "assets/project_splashes";
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Link {
+    label: String,
+    url: String,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Project {
+    title: String,
+    #[serde(default)]
+    splash: Option<String>,
+    description: String,
+    #[serde(default)]
+    links: Vec<Link>,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    year: Option<u16>,
+    #[serde(default)]
+    tech: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Content {
+    projects: Vec<Project>,
+}
+
+fn problems(content: &Content) -> Vec<String> {
+    let mut problems = vec![];
+    for project in &content.projects {
+        if project.title.trim().is_empty() {
+            problems.push("a project has no title".to_string());
+        }
+        if let Some(splash) = &project.splash {
+            if !Path::new(SPLASHES_DIR).join(splash).is_file() {
+                problems.push(format!(
+                    "{}: splash {}/{} doesn't exist",
+                    project.title, SPLASHES_DIR, splash
+                ));
+            }
+        }
+        for link in &project.links {
+            if !link.url.starts_with("https://") && !link.url.starts_with("http://") {
+                problems.push(format!(
+                    "{}: link {} isn't an absolute URL",
+                    project.title, link.url
+                ));
+            }
+        }
+    }
+    problems
+}
+
+fn main() {
+    println!("cargo:rerun-if-changed={}", PROJECTS);
+    println!("cargo:rerun-if-changed={}", SPLASHES_DIR);
+
+    let source = fs::read_to_string(PROJECTS)
+        .unwrap_or_else(|e| panic!("couldn't read {}: {}", PROJECTS, e));
+    let content: Content =
+        toml::from_str(&source).unwrap_or_else(|e| panic!("couldn't parse {}: {}", PROJECTS, e));
+
+    let problems = problems(&content);
+    if !problems.is_empty() {
+        panic!("{} is invalid:\n  {}", PROJECTS, problems.join("\n  "));
+    }
+
+    let out = Path::new(&env::var("OUT_DIR").unwrap()).join("projects.json");
+    fs::write(out, serde_json::to_string(&content.projects).unwrap()).unwrap();
+}
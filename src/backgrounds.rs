@@ -0,0 +1,121 @@
+use gloo::storage::{LocalStorage, Storage};
+use gloo::utils::window;
+use js_sys::Reflect;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::JsValue;
+use web_sys::MediaQueryList;
+
+pub const BACKGROUNDS_DIR: &str = "assets/backgrounds";
+/// Regenerated by `tools/gallery`, with names and authors edited by hand.
+const MANIFEST: &str = include_str!("../assets/backgrounds/manifest.json");
+const PREFERENCES_KEY: &str = "background";
+const REDUCED_MOTION_QUERY: &str = "(prefers-reduced-motion: reduce)";
+
+/// A desktop background from the catalogue.
+#[derive(Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct Background {
+    /// The number its files are named after.
+    pub id: u32,
+    pub name: String,
+    #[serde(default)]
+    pub author: Option<String>,
+    /// File extensions it's available in, best first.
+    pub formats: Vec<String>,
+    /// A small still of the first frame, relative to `BACKGROUNDS_DIR`.
+    #[serde(default)]
+    pub poster: Option<String>,
+    /// The first frame at full size, for the desktop when it's paused.
+    #[serde(default)]
+    pub still: Option<String>,
+    /// Average colour of the first frame as "#rrggbb".
+    #[serde(default)]
+    pub colour: Option<String>,
+}
+impl Background {
+    pub fn url(&self, format: &str) -> String {
+        format!("{}/{}.{}", BACKGROUNDS_DIR, self.id, format)
+    }
+
+    /// Formats a `<video>` can play, with their MIME types.
+    pub fn videos(&self) -> impl Iterator<Item = (String, &'static str)> + '_ {
+        self.formats.iter().filter_map(|format| match format.as_str() {
+            "webm" => Some((self.url(format), "video/webm")),
+            "mp4" => Some((self.url(format), "video/mp4")),
+            _ => None,
+        })
+    }
+
+    pub fn poster_url(&self) -> Option<String> {
+        self.poster
+            .as_ref()
+            .map(|poster| format!("{}/{}", BACKGROUNDS_DIR, poster))
+    }
+
+    /// The full-size still, or the poster if there isn't one.
+    pub fn still_url(&self) -> Option<String> {
+        self.still
+            .as_ref()
+            .map(|still| format!("{}/{}", BACKGROUNDS_DIR, still))
+            .or_else(|| self.poster_url())
+    }
+}
+
+pub fn catalogue() -> Vec<Background> {
+    serde_json::from_str(MANIFEST).expect("backgrounds manifest is valid")
+}
+
+/// What the visitor picked, remembered between visits.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Preferences {
+    /// Id of the chosen background.
+    pub background: Option<u32>,
+    /// Ignore `background` and pick a new one every visit.
+    pub random: bool,
+    /// Show the poster instead of playing the video.
+    #[serde(default)]
+    pub paused: bool,
+}
+impl Default for Preferences {
+    fn default() -> Self {
+        Preferences {
+            background: None,
+            random: true,
+            paused: false,
+        }
+    }
+}
+impl Preferences {
+    pub fn load() -> Preferences {
+        LocalStorage::get(PREFERENCES_KEY).unwrap_or_default()
+    }
+
+    pub fn save(&self) {
+        LocalStorage::set(PREFERENCES_KEY, self).ok();
+    }
+}
+
+/// The `prefers-reduced-motion` media query, to check and listen to.
+pub fn reduced_motion_query() -> Option<MediaQueryList> {
+    window().match_media(REDUCED_MOTION_QUERY).ok().flatten()
+}
+
+pub fn prefers_reduced_motion() -> bool {
+    reduced_motion_query().is_some_and(|query| query.matches())
+}
+
+/// Whether the browser says the visitor is saving data or on a slow
+/// connection, from the Network Information API where there is one.
+pub fn saving_data() -> bool {
+    let connection = match Reflect::get(&window().navigator(), &"connection".into()) {
+        Ok(connection) if connection.is_object() => connection,
+        _ => return false,
+    };
+    let get = |key: &str| Reflect::get(&connection, &JsValue::from_str(key)).ok();
+
+    let save_data = get("saveData").and_then(|v| v.as_bool()).unwrap_or(false);
+    let slow = matches!(
+        get("effectiveType").and_then(|v| v.as_string()).as_deref(),
+        Some("slow-2g" | "2g")
+    );
+    save_data || slow
+}
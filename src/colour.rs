@@ -0,0 +1,194 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use futures::channel::oneshot;
+use gloo::events::EventListener;
+use gloo::utils::document;
+use wasm_bindgen::JsCast;
+use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement, HtmlImageElement};
+
+/// Images are scaled down to this many pixels square before quantising.
+const SAMPLE_SIZE: u32 = 32;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+impl Rgb {
+    pub fn css(&self) -> String {
+        format!("rgb({}, {}, {})", self.0, self.1, self.2)
+    }
+
+    /// Relative luminance as defined by WCAG, between 0 and 1.
+    pub fn luminance(&self) -> f64 {
+        fn channel(c: u8) -> f64 {
+            let c = c as f64 / 255.0;
+            if c <= 0.03928 {
+                c / 12.92
+            } else {
+                ((c + 0.055) / 1.055).powf(2.4)
+            }
+        }
+        0.2126 * channel(self.0) + 0.7152 * channel(self.1) + 0.0722 * channel(self.2)
+    }
+
+    fn distance(&self, other: &Rgb) -> u32 {
+        let dr = self.0 as i32 - other.0 as i32;
+        let dg = self.1 as i32 - other.1 as i32;
+        let db = self.2 as i32 - other.2 as i32;
+        (dr * dr + dg * dg + db * db) as u32
+    }
+
+    fn darken(&self, amount: f64) -> Rgb {
+        let scale = |c: u8| (c as f64 * (1.0 - amount)) as u8;
+        Rgb(scale(self.0), scale(self.1), scale(self.2))
+    }
+}
+
+/// Colours pulled from an image, used to theme window chrome.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Accent {
+    pub primary: Rgb,
+    pub secondary: Rgb,
+    /// Black or white, whichever is more readable on top of `primary`.
+    pub text: Rgb,
+}
+impl Accent {
+    /// Picks the most common colour plus the most contrasting one to pair with it.
+    pub fn from_palette(palette: &[Rgb]) -> Option<Accent> {
+        let primary = *palette.first()?;
+        let secondary = palette
+            .iter()
+            .skip(1)
+            .max_by_key(|c| c.distance(&primary))
+            .copied()
+            .unwrap_or_else(|| primary.darken(0.4));
+        let text = if primary.luminance() > 0.4 {
+            Rgb(0, 0, 0)
+        } else {
+            Rgb(255, 255, 255)
+        };
+
+        Some(Accent {
+            primary,
+            secondary,
+            text,
+        })
+    }
+
+    /// CSS custom properties consumed by `.window.accented` in style.css.
+    pub fn css_variables(&self) -> String {
+        format!(
+            "--accent-start: {}; --accent-end: {}; --accent-text: {};",
+            self.primary.css(),
+            self.secondary.css(),
+            self.text.css()
+        )
+    }
+}
+
+/// Median cut quantisation of RGBA pixel data, most common colours first.
+///
+/// Mostly transparent pixels are ignored.
+pub fn dominant_colours(rgba: &[u8], count: usize) -> Vec<Rgb> {
+    let pixels: Vec<[u8; 3]> = rgba
+        .chunks_exact(4)
+        .filter(|p| p[3] >= 128)
+        .map(|p| [p[0], p[1], p[2]])
+        .collect();
+    if pixels.is_empty() || count == 0 {
+        return vec![];
+    }
+
+    // Range of the widest channel in a bucket and which channel that is.
+    fn widest_channel(bucket: &[[u8; 3]]) -> (u8, usize) {
+        (0..3)
+            .map(|c| {
+                let min = bucket.iter().map(|p| p[c]).min().unwrap_or(0);
+                let max = bucket.iter().map(|p| p[c]).max().unwrap_or(0);
+                (max - min, c)
+            })
+            .max()
+            .unwrap_or((0, 0))
+    }
+
+    let mut buckets = vec![pixels];
+    while buckets.len() < count {
+        let split = buckets
+            .iter()
+            .enumerate()
+            .filter(|(_, b)| b.len() > 1)
+            .map(|(i, b)| (widest_channel(b), i))
+            .max();
+
+        match split {
+            Some(((range, channel), i)) if range > 0 => {
+                let mut bucket = buckets.swap_remove(i);
+                bucket.sort_unstable_by_key(|p| p[channel]);
+                let upper = bucket.split_off(bucket.len() / 2);
+                buckets.push(bucket);
+                buckets.push(upper);
+            }
+            _ => break,
+        }
+    }
+
+    buckets.sort_by_key(|b| std::cmp::Reverse(b.len()));
+    buckets
+        .iter()
+        .map(|bucket| {
+            let len = bucket.len() as u32;
+            let sum = bucket.iter().fold([0u32; 3], |mut sum, p| {
+                sum.iter_mut().zip(p).for_each(|(s, c)| *s += *c as u32);
+                sum
+            });
+            Rgb(
+                (sum[0] / len) as u8,
+                (sum[1] / len) as u8,
+                (sum[2] / len) as u8,
+            )
+        })
+        .collect()
+}
+
+/// Loads `url` into an image element that can be drawn to a canvas.
+pub async fn load_image(url: &str) -> Option<HtmlImageElement> {
+    let image = HtmlImageElement::new().ok()?;
+    image.set_cross_origin(Some("anonymous"));
+
+    let (tx, rx) = oneshot::channel();
+    let tx = Rc::new(RefCell::new(Some(tx)));
+    let load_tx = tx.clone();
+    let _load = EventListener::once(&image, "load", move |_| {
+        if let Some(tx) = load_tx.borrow_mut().take() {
+            tx.send(true).ok();
+        }
+    });
+    let _error = EventListener::once(&image, "error", move |_| {
+        if let Some(tx) = tx.borrow_mut().take() {
+            tx.send(false).ok();
+        }
+    });
+    image.set_src(url);
+
+    rx.await.unwrap_or(false).then_some(image)
+}
+
+/// Loads an image, draws it onto an offscreen canvas and quantises its pixels.
+///
+/// Returns `None` if the image fails to load or the canvas is tainted because
+/// the image host doesn't allow cross origin reads.
+pub async fn palette_from_image(url: &str, count: usize) -> Option<Vec<Rgb>> {
+    let image = load_image(url).await?;
+
+    let canvas: HtmlCanvasElement = document().create_element("canvas").ok()?.dyn_into().ok()?;
+    canvas.set_width(SAMPLE_SIZE);
+    canvas.set_height(SAMPLE_SIZE);
+    let context: CanvasRenderingContext2d = canvas.get_context("2d").ok()??.dyn_into().ok()?;
+
+    let size = SAMPLE_SIZE as f64;
+    context
+        .draw_image_with_html_image_element_and_dw_and_dh(&image, 0.0, 0.0, size, size)
+        .ok()?;
+    let data = context.get_image_data(0.0, 0.0, size, size).ok()?.data();
+
+    Some(dominant_colours(&data, count))
+}
@@ -1,15 +1,22 @@
 use std::collections::BTreeMap;
 
+use crate::backgrounds::{catalogue, reduced_motion_query, saving_data, Background, Preferences};
+use crate::colour::Accent;
+use crate::schemes::Scheme;
+use crate::screensavers::{Saver, Settings as ScreenSaverSettings};
+use crate::time_format;
 use crate::window::{Window, WindowClose, WindowId, WindowPosition, WindowState};
-use crate::{NoteJson, MAX_BACKGROUND_INDEX};
+use crate::windows::ScreenSaver;
+use crate::NoteJson;
 use gloo::events::EventListener;
 use gloo::net::http::Request;
-use gloo::timers::callback::Interval;
+use gloo::timers::callback::{Interval, Timeout};
 use gloo::utils::{document, window as browser_window};
 use js_sys::Date;
+use rand::Rng;
 use urlencoding::encode;
 use wasm_bindgen::JsCast;
-use wasm_bindgen_futures::spawn_local;
+use wasm_bindgen_futures::{spawn_local, JsFuture};
 use web_sys::{Element, EventTarget, HtmlElement, HtmlTextAreaElement, HtmlVideoElement};
 use yew::context::ContextHandle;
 use yew::events::{MouseEvent, TouchEvent};
@@ -19,11 +26,14 @@ use yew::NodeRef;
 
 use std::rc::Rc;
 
+/// Input events that count as the visitor still being there.
+const INPUT_EVENTS: [&str; 5] = ["mousemove", "mousedown", "keydown", "touchstart", "wheel"];
+/// Input right after a screensaver starts is ignored, so the click on
+/// Preview or a nudged mouse doesn't stop it straight away.
+const SCREENSAVER_GRACE_MS: f64 = 1000.0;
+
 fn get_time_string() -> String {
-    let now = Date::new_0();
-    let hours = now.get_hours();
-    let minutes = now.get_minutes();
-    format!("{:02}:{:02}", hours, minutes)
+    time_format::format_clock(&Date::new_0())
 }
 
 #[derive(Debug)]
@@ -39,44 +49,126 @@ impl MoveEvent {
         }
     }
 
-    fn prevent_default(&self) {
+    pub fn prevent_default(&self) {
         match self {
             MoveEvent::MouseEvent(e) => e.prevent_default(),
             MoveEvent::TouchEvent(_) => (), // TouchEvent doesn't suport prevent_default()
         };
     }
 
-    fn client_x(&self) -> i32 {
+    pub fn client_x(&self) -> i32 {
         match self {
             MoveEvent::MouseEvent(e) => e.client_x(),
             MoveEvent::TouchEvent(e) => e.target_touches().get(0).unwrap().client_x(),
         }
     }
 
-    fn client_y(&self) -> i32 {
+    pub fn client_y(&self) -> i32 {
         match self {
             MoveEvent::MouseEvent(e) => e.client_y(),
             MoveEvent::TouchEvent(e) => e.target_touches().get(0).unwrap().client_y(),
         }
     }
+
+    /// Distance between the first two fingers, if this is a multi-touch event.
+    pub fn touch_distance(&self) -> Option<f64> {
+        match self {
+            MoveEvent::MouseEvent(_) => None,
+            MoveEvent::TouchEvent(e) => {
+                let touches = e.touches();
+                let (a, b) = (touches.get(0)?, touches.get(1)?);
+                let dx = (a.client_x() - b.client_x()) as f64;
+                let dy = (a.client_y() - b.client_y()) as f64;
+                Some(dx.hypot(dy))
+            }
+        }
+    }
+}
+
+/// A balloon tip popped up from the taskbar tray.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Notification {
+    pub title: String,
+    pub body: String,
+    pub icon: String,
 }
 
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct Theme {
-    pub background: u32,
+    pub backgrounds: Rc<Vec<Background>>,
+    /// Index into `backgrounds`.
+    pub background: usize,
+    /// Whether a new background is picked on every visit.
+    pub random: bool,
+    /// Whether the visitor has paused the background video.
+    pub paused: bool,
+    /// Colours and font sizes for the window chrome.
+    pub scheme: &'static Scheme,
+    pub screensaver: ScreenSaverSettings,
+}
+impl Theme {
+    /// The background catalogue with the visitor's saved choice, or a random
+    /// background if they haven't picked one or asked for a new one each time,
+    /// along with their colour scheme.
+    pub fn load() -> Theme {
+        let backgrounds = catalogue();
+        let preferences = Preferences::load();
+        let saved = preferences
+            .background
+            .filter(|_| !preferences.random)
+            .and_then(|id| backgrounds.iter().position(|b| b.id == id));
+        let background =
+            saved.unwrap_or_else(|| rand::thread_rng().gen_range(0..backgrounds.len().max(1)));
+
+        Theme {
+            backgrounds: Rc::new(backgrounds),
+            background,
+            random: preferences.random,
+            paused: preferences.paused,
+            scheme: Scheme::load(),
+            screensaver: ScreenSaverSettings::load(),
+        }
+    }
+
+    pub fn current(&self) -> Option<&Background> {
+        self.backgrounds.get(self.background)
+    }
+}
+
+pub enum ThemeAction {
+    Select(usize),
+    SetRandom(bool),
+    SetPaused(bool),
+    SetScheme(&'static Scheme),
+    SetScreenSaver(ScreenSaverSettings),
 }
 impl Reducible for Theme {
-    type Action = u32;
+    type Action = ThemeAction;
+
+    fn reduce(self: Rc<Self>, action: Self::Action) -> Rc<Self> {
+        let count = self.backgrounds.len().max(1);
+        let mut theme = (*self).clone();
+        match action {
+            ThemeAction::Select(background) => theme.background = background % count,
+            ThemeAction::SetRandom(random) => theme.random = random,
+            ThemeAction::SetPaused(paused) => theme.paused = paused,
+            ThemeAction::SetScheme(scheme) => {
+                scheme.save();
+                theme.scheme = scheme;
+            }
+            ThemeAction::SetScreenSaver(screensaver) => {
+                screensaver.save();
+                theme.screensaver = screensaver;
+            }
+        }
 
-    fn reduce(self: Rc<Self>, background: Self::Action) -> Rc<Self> {
-        let background = if background == 0 {
-            MAX_BACKGROUND_INDEX - 1
-        } else if background == MAX_BACKGROUND_INDEX {
-            1
-        } else {
-            background
-        };
-        Theme { background }.into()
+        Preferences {
+            background: theme.current().map(|b| b.id),
+            random: theme.random,
+            paused: theme.paused,
+        }
+        .save();
+        theme.into()
     }
 }
 pub type ThemeContext = UseReducerHandle<Theme>;
@@ -88,6 +180,7 @@ pub enum CoplandMsg {
     FocusWindow(WindowId),
     CloseWindow(WindowId),
     ResizeWindow(WindowId, Option<u32>),
+    SetWindowAccent(WindowId, Option<Accent>),
     DragWindowStart(WindowId, MoveEvent),
     DragWindowMove(WindowId, MoveEvent),
     DragWindowEnd(WindowId),
@@ -96,7 +189,13 @@ pub enum CoplandMsg {
     RestoreWindow(WindowId),
     ResizeBrowser,
     ThemeContextUpdated(ThemeContext),
+    ReducedMotionChanged(bool),
+    VisibilityChanged,
+    UserInput,
+    StartScreenSaver(Saver, String),
     UpdateTaskbarTime,
+    Notify(Notification),
+    DismissNotification,
 }
 
 pub struct Copland {
@@ -110,13 +209,109 @@ pub struct Copland {
     mouse_offset_y: i32,
     theme: ThemeContext,
     _theme_listener: ContextHandle<ThemeContext>,
+    reduced_motion: bool,
+    saving_data: bool,
+    _reduced_motion_listener: Option<EventListener>,
+    _visibility_listener: EventListener,
+    /// When the visitor last touched anything, from `Date::now()`.
+    last_input: f64,
+    /// The screensaver that's running, with the marquee's text.
+    screensaver: Option<(Saver, String)>,
+    screensaver_started: f64,
+    _input_listeners: Vec<EventListener>,
     mouse_move_listener: Option<EventListener>,
     mouse_up_listener: Option<EventListener>,
     touch_move_listener: Option<EventListener>,
     touch_up_listener: Option<EventListener>,
     did_move: bool,
+    notification: Option<Notification>,
+    notification_timeout: Option<Timeout>,
 }
 impl Copland {
+    /// Whether to show a still instead of playing the background video, for
+    /// visitors who asked for less motion or less data, or paused it.
+    fn still_background(&self) -> bool {
+        self.theme.paused || self.reduced_motion || self.saving_data
+    }
+
+    fn view_background(&self) -> Html {
+        let background = self.theme.current();
+        let colour = background
+            .and_then(|b| b.colour.clone())
+            .map(|c| format!("background-color: {};", c));
+
+        if self.still_background() {
+            let still = background.and_then(|b| b.still_url());
+            // Without a still, a video that isn't playing shows its first frame.
+            if let (Some(background), None) = (background, &still) {
+                return html! {
+                    <video class="background" key={background.id} playsinline=true muted=true
+                        preload="metadata" style={colour}
+                    >
+                        {
+                            background.videos().map(|(src, mime)| html! {
+                                <source {src} type={mime}/>
+                            }).collect::<Html>()
+                        }
+                    </video>
+                };
+            }
+            let still = still.map(|still| format!("background-image: url(\"{}\");", still));
+            let style = format!(
+                "{}{}",
+                colour.unwrap_or_default(),
+                still.unwrap_or_default()
+            );
+            return html! {
+                <div class="background background-still" {style}></div>
+            };
+        }
+
+        html! {
+            <video class="background" playsinline=true autoplay={true} muted=true loop=true
+                ref={self.background_video.clone()}
+                poster={background.and_then(|b| b.poster_url())}
+                style={colour}
+            >
+                {
+                    background.map(|b| b.videos().map(|(src, mime)| html! {
+                        <source {src} type={mime}/>
+                    }).collect::<Html>()).unwrap_or_default()
+                }
+            </video>
+        }
+    }
+
+    fn view_wallpaper_toggle(&self) -> Html {
+        let paused = self.theme.paused;
+        let (title, disabled) = if self.reduced_motion {
+            ("Wallpaper paused for reduced motion", true)
+        } else if self.saving_data {
+            ("Wallpaper paused to save data", true)
+        } else if paused {
+            ("Play wallpaper", false)
+        } else {
+            ("Pause wallpaper", false)
+        };
+        let onclick = {
+            let theme = self.theme.clone();
+            Callback::from(move |_| theme.dispatch(ThemeAction::SetPaused(!paused)))
+        };
+
+        html! {
+            <button
+                class="wallpaper-toggle"
+                {title}
+                aria-label={title}
+                aria-pressed={(paused || disabled).to_string()}
+                {disabled}
+                {onclick}
+            >
+                <img class="title-bar-icon" src="assets/icons/active_movie-0.png" alt="" />
+            </button>
+        }
+    }
+
     fn view_taskbar_button(&self, window: &Window, link: &Scope<Self>) -> Html {
         if window.state == WindowState::Hidden {
             return html! {
@@ -149,6 +344,26 @@ impl Copland {
         }
     }
 
+    fn view_notification(&self, link: &Scope<Self>) -> Html {
+        if let Some(notification) = self.notification.as_ref() {
+            html! {
+                <div
+                    class="balloon"
+                    role="status"
+                    onclick={link.callback(|_| CoplandMsg::DismissNotification)}
+                >
+                    <div class="balloon-title">
+                        <img class="title-bar-icon" src={notification.icon.clone()} alt="notification icon" />
+                        <b>{ notification.title.clone() }</b>
+                    </div>
+                    <p>{ notification.body.clone() }</p>
+                </div>
+            }
+        } else {
+            html! {}
+        }
+    }
+
     pub fn update_sticky_note(id: usize) {
         spawn_local(async move {
             let content_el = document()
@@ -202,6 +417,39 @@ impl Component for Copland {
             .context(ctx.link().callback(CoplandMsg::ThemeContextUpdated))
             .expect("No ThemeContext provided");
 
+        let reduced_motion_query = reduced_motion_query();
+        let reduced_motion = reduced_motion_query
+            .as_ref()
+            .is_some_and(|query| query.matches());
+        let reduced_motion_listener = reduced_motion_query.map(|query| {
+            let on_change = ctx.link().callback(CoplandMsg::ReducedMotionChanged);
+            let target = query.clone();
+            EventListener::new(&query, "change", move |_| {
+                on_change.emit(target.matches());
+            })
+        });
+        let on_visibility = ctx.link().callback(|_| CoplandMsg::VisibilityChanged);
+        let visibility_listener = EventListener::new(&document(), "visibilitychange", move |_| {
+            on_visibility.emit(());
+        });
+
+        let input_listeners = INPUT_EVENTS
+            .iter()
+            .map(|&event| {
+                let on_input = ctx.link().callback(|_| CoplandMsg::UserInput);
+                EventListener::new(&document(), event, move |e| {
+                    // Ignore the odd pixel of jitter from a resting mouse.
+                    let jitter = e
+                        .dyn_ref::<MouseEvent>()
+                        .filter(|e| e.type_() == "mousemove")
+                        .is_some_and(|e| e.movement_x().abs() + e.movement_y().abs() < 3);
+                    if !jitter {
+                        on_input.emit(());
+                    }
+                })
+            })
+            .collect();
+
         let windows = vec![Window::home(ctx.link())];
         let windows: BTreeMap<WindowId, Window> = windows.into_iter().map(|w| (w.id, w)).collect();
         let max_z_index = windows.len().try_into().unwrap();
@@ -257,23 +505,64 @@ impl Component for Copland {
             mouse_offset_y: 0,
             theme,
             _theme_listener: theme_listener,
+            reduced_motion,
+            saving_data: saving_data(),
+            _reduced_motion_listener: reduced_motion_listener,
+            _visibility_listener: visibility_listener,
+            last_input: Date::now(),
+            screensaver: None,
+            screensaver_started: 0.0,
+            _input_listeners: input_listeners,
             mouse_move_listener: None,
             mouse_up_listener: None,
             touch_move_listener: None,
             touch_up_listener: None,
             did_move: false,
+            notification: None,
+            notification_timeout: None,
         }
     }
 
     fn update(&mut self, ctx: &Context<Self>, copland_msg: Self::Message) -> bool {
         match copland_msg {
             CoplandMsg::ThemeContextUpdated(theme) => {
+                let changed = theme.background != self.theme.background;
                 self.theme = theme;
-                let el = self.background_video.cast::<HtmlVideoElement>().unwrap();
-                el.load();
+                if changed {
+                    if let Some(el) = self.background_video.cast::<HtmlVideoElement>() {
+                        el.load();
+                    }
+                }
                 // el.onloadeddata().unwrap().
                 true
             }
+            CoplandMsg::ReducedMotionChanged(reduced_motion) => {
+                self.reduced_motion = reduced_motion;
+                true
+            }
+            CoplandMsg::VisibilityChanged => {
+                // Coming back to the tab counts as input, so the screensaver
+                // doesn't start the moment they return.
+                if !document().hidden() {
+                    self.last_input = Date::now();
+                }
+                // No point decoding frames nobody can see.
+                if let Some(el) = self.background_video.cast::<HtmlVideoElement>() {
+                    if document().hidden() {
+                        el.pause().ok();
+                    } else if let Ok(playing) = el.play() {
+                        // Autoplay can be refused, say in power saving mode,
+                        // so leave the video paused rather than fail loudly.
+                        spawn_local(async move {
+                            if let Err(e) = JsFuture::from(playing).await {
+                                log::info!("background video wouldn't resume: {:?}", e);
+                                el.pause().ok();
+                            }
+                        });
+                    }
+                }
+                false
+            }
             CoplandMsg::NewSticky => {
                 log::info!("creating new sticky note");
 
@@ -374,6 +663,12 @@ impl Component for Copland {
                 }
                 true
             }
+            CoplandMsg::SetWindowAccent(window_id, accent) => {
+                if let Some(window) = self.windows.get_mut(&window_id) {
+                    window.accent = accent;
+                }
+                true
+            }
             CoplandMsg::DragWindowStart(window_id, e) => {
                 log::info!("started dragging window");
 
@@ -538,6 +833,50 @@ impl Component for Copland {
             }
             CoplandMsg::UpdateTaskbarTime => {
                 self.taskbar_time = get_time_string();
+
+                let settings = &self.theme.screensaver;
+                let idle = Date::now() - self.last_input;
+                // Nobody's watching a hidden tab, so don't animate for them.
+                if let (None, Some(saver), false) =
+                    (&self.screensaver, settings.saver, document().hidden())
+                {
+                    if idle >= settings.wait as f64 * 60_000.0 {
+                        ctx.link().send_message(CoplandMsg::StartScreenSaver(
+                            saver,
+                            settings.text.clone(),
+                        ));
+                    }
+                }
+                true
+            }
+            CoplandMsg::UserInput => {
+                let now = Date::now();
+                self.last_input = now;
+                if self.screensaver.is_some()
+                    && now - self.screensaver_started > SCREENSAVER_GRACE_MS
+                {
+                    self.screensaver = None;
+                    return true;
+                }
+                false
+            }
+            CoplandMsg::StartScreenSaver(saver, text) => {
+                log::info!("starting screensaver");
+                self.screensaver = Some((saver, text));
+                self.screensaver_started = Date::now();
+                self.last_input = self.screensaver_started;
+                true
+            }
+            CoplandMsg::Notify(notification) => {
+                log::info!("showing notification");
+                let dismiss = ctx.link().callback(|_| CoplandMsg::DismissNotification);
+                self.notification_timeout = Some(Timeout::new(6_000, move || dismiss.emit(())));
+                self.notification = Some(notification);
+                true
+            }
+            CoplandMsg::DismissNotification => {
+                self.notification = None;
+                self.notification_timeout = None;
                 true
             }
         }
@@ -556,13 +895,8 @@ impl Component for Copland {
         let create_sticky = ctx.link().callback(|_| CoplandMsg::NewSticky);
 
         html! {
-            <div id="copland" class="copland">
-                <video class="background" playsinline=true autoplay={true} muted=true loop=true
-                    ref={self.background_video.clone()}
-                >
-                    <source src={format!("assets/backgrounds/{}.webm", self.theme.background)} type="video/webm"/>
-                    <source src={format!("assets/backgrounds/{}.mp4", self.theme.background)} type="video/mp4"/>
-                </video>
+            <div id="copland" class="copland" style={self.theme.scheme.css_variables()}>
+                { self.view_background() }
                 <div id="window-area"
                     class="window-area"
                     ref={self.window_area.clone()}
@@ -580,15 +914,22 @@ impl Component for Copland {
                         }).collect::<Html>()
                     }
                     <div class="taskbar-tools">
+                        { self.view_notification(ctx.link()) }
+                        { self.view_wallpaper_toggle() }
                         <button class="new-sticky" onclick={create_sticky}>
                             <span><b>{ "add" }</b></span>
                             <img class="title-bar-icon" src="assets/icons/template_empty-5.png" alt="add-sticky-icon" />
                         </button>
-                        <div class="taskbar-time">
+                        <div class="taskbar-time" title={time_format::format_date_time(&Date::new_0())}>
                             {self.taskbar_time.clone()}
                         </div>
                     </div>
                 </div>
+                if let Some((saver, text)) = self.screensaver.clone() {
+                    <div class="screensaver">
+                        <ScreenSaver {saver} {text} />
+                    </div>
+                }
             </div>
         }
     }
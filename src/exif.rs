@@ -0,0 +1,689 @@
+//! A small EXIF reader for the photo viewer's Properties dialog.
+//!
+//! Only the handful of tags we show are decoded. The TIFF structure is read
+//! out of a JPEG's APP1 segment or a PNG's `eXIf` chunk.
+
+const JPEG_SOI: [u8; 2] = [0xFF, 0xD8];
+const PNG_SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+
+// IFD0
+const MAKE: u16 = 0x010F;
+const MODEL: u16 = 0x0110;
+const DATE_TIME: u16 = 0x0132;
+const EXIF_IFD: u16 = 0x8769;
+const GPS_IFD: u16 = 0x8825;
+// Exif IFD
+const EXPOSURE_TIME: u16 = 0x829A;
+const F_NUMBER: u16 = 0x829D;
+const ISO: u16 = 0x8827;
+const DATE_TIME_ORIGINAL: u16 = 0x9003;
+const FOCAL_LENGTH: u16 = 0x920A;
+const LENS_MAKE: u16 = 0xA433;
+const LENS_MODEL: u16 = 0xA434;
+// GPS IFD
+const GPS_LATITUDE_REF: u16 = 0x0001;
+const GPS_LATITUDE: u16 = 0x0002;
+const GPS_LONGITUDE_REF: u16 = 0x0003;
+const GPS_LONGITUDE: u16 = 0x0004;
+const GPS_ALTITUDE_REF: u16 = 0x0005;
+const GPS_ALTITUDE: u16 = 0x0006;
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Gps {
+    /// Decimal degrees, negative in the southern hemisphere.
+    pub latitude: f64,
+    /// Decimal degrees, negative west of Greenwich.
+    pub longitude: f64,
+    /// Metres above sea level.
+    pub altitude: Option<f64>,
+}
+impl Gps {
+    pub fn format(&self) -> String {
+        format!(
+            "{:.5}° {}, {:.5}° {}",
+            self.latitude.abs(),
+            if self.latitude < 0.0 { "S" } else { "N" },
+            self.longitude.abs(),
+            if self.longitude < 0.0 { "W" } else { "E" },
+        )
+    }
+
+    pub fn map_url(&self) -> String {
+        format!(
+            "https://www.openstreetmap.org/?mlat={}&mlon={}#map=15/{}/{}",
+            self.latitude, self.longitude, self.latitude, self.longitude
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Exif {
+    pub make: Option<String>,
+    pub model: Option<String>,
+    pub lens_make: Option<String>,
+    pub lens_model: Option<String>,
+    /// Shutter speed as a fraction of a second.
+    pub exposure_time: Option<(u32, u32)>,
+    pub f_number: Option<f64>,
+    pub iso: Option<u32>,
+    /// In millimetres.
+    pub focal_length: Option<f64>,
+    /// As written by the camera, `YYYY:MM:DD HH:MM:SS`.
+    pub date_taken: Option<String>,
+    pub gps: Option<Gps>,
+}
+impl Exif {
+    /// Make and model, without repeating the make when the model includes it.
+    pub fn camera(&self) -> Option<String> {
+        join_make_model(self.make.as_deref(), self.model.as_deref())
+    }
+
+    pub fn lens(&self) -> Option<String> {
+        join_make_model(self.lens_make.as_deref(), self.lens_model.as_deref())
+    }
+
+    pub fn exposure(&self) -> Option<String> {
+        let (numerator, denominator) = self.exposure_time?;
+        if numerator == 0 || denominator == 0 {
+            return None;
+        }
+        Some(if numerator >= denominator {
+            format!("{} s", numerator as f64 / denominator as f64)
+        } else {
+            format!("1/{} s", (denominator as f64 / numerator as f64).round())
+        })
+    }
+
+    pub fn aperture(&self) -> Option<String> {
+        self.f_number.map(|f| format!("f/{}", f))
+    }
+
+    pub fn focal(&self) -> Option<String> {
+        self.focal_length.map(|mm| format!("{} mm", mm))
+    }
+
+    /// The date taken as `YYYY-MM-DD HH:MM:SS`.
+    pub fn date(&self) -> Option<String> {
+        let date = self.date_taken.as_ref()?;
+        Some(date.replacen(':', "-", 2))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Exif::default()
+    }
+}
+
+fn join_make_model(make: Option<&str>, model: Option<&str>) -> Option<String> {
+    match (make, model) {
+        (Some(make), Some(model)) if model.starts_with(make) => Some(model.to_string()),
+        (Some(make), Some(model)) => Some(format!("{} {}", make, model)),
+        (make, model) => make.or(model).map(str::to_string),
+    }
+}
+
+/// The TIFF-structured EXIF block inside a JPEG or PNG file, if it has one.
+pub fn find_exif(bytes: &[u8]) -> Option<&[u8]> {
+    if bytes.starts_with(&JPEG_SOI) {
+        let mut pos = 2;
+        while pos + 4 <= bytes.len() {
+            if bytes[pos] != 0xFF {
+                return None;
+            }
+            let marker = bytes[pos + 1];
+            // Start of scan: the image data follows, no more metadata.
+            if marker == 0xDA || marker == 0xD9 {
+                return None;
+            }
+            let length = u16::from_be_bytes([bytes[pos + 2], bytes[pos + 3]]) as usize;
+            let end = pos.checked_add(2)?.checked_add(length)?;
+            let segment = bytes.get(pos + 4..end)?;
+            if marker == 0xE1 && segment.starts_with(b"Exif\0\0") {
+                return Some(&segment[6..]);
+            }
+            pos = end;
+        }
+        None
+    } else if bytes.starts_with(&PNG_SIGNATURE) {
+        let mut pos = PNG_SIGNATURE.len();
+        while pos + 8 <= bytes.len() {
+            let length = u32::from_be_bytes(bytes[pos..pos + 4].try_into().ok()?) as usize;
+            let kind = &bytes[pos + 4..pos + 8];
+            let end = pos.checked_add(8)?.checked_add(length)?;
+            let data = bytes.get(pos + 8..end)?;
+            match kind {
+                b"eXIf" => return Some(data),
+                b"IDAT" | b"IEND" => return None,
+                // Skip the CRC too.
+                _ => pos = end.checked_add(4)?,
+            }
+        }
+        None
+    } else {
+        None
+    }
+}
+
+/// Reads values from a TIFF block in either byte order.
+struct Tiff<'a> {
+    data: &'a [u8],
+    little_endian: bool,
+}
+
+/// An IFD entry's type, count and the offset of its value.
+struct Entry {
+    kind: u16,
+    count: u32,
+    offset: usize,
+}
+
+impl<'a> Tiff<'a> {
+    fn new(data: &'a [u8]) -> Option<Tiff<'a>> {
+        let little_endian = match data.get(0..2)? {
+            b"II" => true,
+            b"MM" => false,
+            _ => return None,
+        };
+        let tiff = Tiff {
+            data,
+            little_endian,
+        };
+        (tiff.u16(2)? == 42).then_some(tiff)
+    }
+
+    /// The `N` bytes at `offset`, if they're all there.
+    fn bytes<const N: usize>(&self, offset: usize) -> Option<[u8; N]> {
+        self.data
+            .get(offset..offset.checked_add(N)?)?
+            .try_into()
+            .ok()
+    }
+
+    fn u16(&self, offset: usize) -> Option<u16> {
+        let bytes = self.bytes(offset)?;
+        Some(if self.little_endian {
+            u16::from_le_bytes(bytes)
+        } else {
+            u16::from_be_bytes(bytes)
+        })
+    }
+
+    fn u32(&self, offset: usize) -> Option<u32> {
+        let bytes = self.bytes(offset)?;
+        Some(if self.little_endian {
+            u32::from_le_bytes(bytes)
+        } else {
+            u32::from_be_bytes(bytes)
+        })
+    }
+
+    fn first_ifd(&self) -> Option<usize> {
+        self.u32(4).map(|offset| offset as usize)
+    }
+
+    /// The entries of the IFD at `offset`, keyed by tag.
+    fn entries(&self, offset: usize) -> Vec<(u16, Entry)> {
+        let count = match self.u16(offset) {
+            Some(count) => count as usize,
+            None => return Vec::new(),
+        };
+        (0..count)
+            .filter_map(|i| {
+                let entry = offset.checked_add(2 + i * 12)?;
+                let tag = self.u16(entry)?;
+                let kind = self.u16(entry.checked_add(2)?)?;
+                let count = self.u32(entry.checked_add(4)?)?;
+                let size = type_size(kind)?.checked_mul(count as usize)?;
+                // Values of four bytes or fewer are stored in the entry itself.
+                let value = entry.checked_add(8)?;
+                let offset = if size <= 4 {
+                    value
+                } else {
+                    self.u32(value)? as usize
+                };
+                // Skip values that claim to run past the end of the data.
+                if offset.checked_add(size)? > self.data.len() {
+                    return None;
+                }
+                Some((
+                    tag,
+                    Entry {
+                        kind,
+                        count,
+                        offset,
+                    },
+                ))
+            })
+            .collect()
+    }
+
+    fn ascii(&self, entry: &Entry) -> Option<String> {
+        let end = entry.offset.checked_add(entry.count as usize)?;
+        let bytes = self.data.get(entry.offset..end)?;
+        let text = String::from_utf8_lossy(bytes);
+        let text = text.trim_end_matches('\0').trim();
+        (!text.is_empty()).then(|| text.to_string())
+    }
+
+    fn unsigned(&self, entry: &Entry) -> Option<u32> {
+        match entry.kind {
+            1 => self.data.get(entry.offset).map(|b| *b as u32),
+            3 => self.u16(entry.offset).map(u32::from),
+            4 => self.u32(entry.offset),
+            _ => None,
+        }
+    }
+
+    /// The numerator and denominator of the `index`th value, which are
+    /// signed for SRATIONAL entries.
+    fn fraction(&self, entry: &Entry, index: usize) -> Option<(u32, u32)> {
+        let offset = entry.offset.checked_add(index.checked_mul(8)?)?;
+        Some((self.u32(offset)?, self.u32(offset.checked_add(4)?)?))
+    }
+
+    fn rational(&self, entry: &Entry, index: usize) -> Option<(u32, u32)> {
+        (entry.kind == 5).then(|| self.fraction(entry, index))?
+    }
+
+    fn decimal(&self, entry: &Entry, index: usize) -> Option<f64> {
+        let (numerator, denominator) = self.fraction(entry, index)?;
+        let (numerator, denominator) = match entry.kind {
+            5 => (numerator as f64, denominator as f64),
+            10 => (numerator as i32 as f64, denominator as i32 as f64),
+            _ => return None,
+        };
+        (denominator != 0.0).then(|| numerator / denominator)
+    }
+
+    /// Degrees, minutes and seconds as decimal degrees.
+    fn coordinate(&self, entry: &Entry) -> Option<f64> {
+        Some(
+            self.decimal(entry, 0)?
+                + self.decimal(entry, 1)? / 60.0
+                + self.decimal(entry, 2)? / 3600.0,
+        )
+    }
+}
+
+fn type_size(kind: u16) -> Option<usize> {
+    match kind {
+        1 | 2 | 6 | 7 => Some(1),
+        3 | 8 => Some(2),
+        4 | 9 | 11 => Some(4),
+        5 | 10 | 12 => Some(8),
+        _ => None,
+    }
+}
+
+fn find(entries: &[(u16, Entry)], tag: u16) -> Option<&Entry> {
+    entries.iter().find(|(t, _)| *t == tag).map(|(_, e)| e)
+}
+
+fn parse_gps(tiff: &Tiff, entries: &[(u16, Entry)]) -> Option<Gps> {
+    let reference = |tag| find(entries, tag).and_then(|e| tiff.ascii(e));
+
+    let mut latitude = tiff.coordinate(find(entries, GPS_LATITUDE)?)?;
+    if reference(GPS_LATITUDE_REF).as_deref() == Some("S") {
+        latitude = -latitude;
+    }
+    let mut longitude = tiff.coordinate(find(entries, GPS_LONGITUDE)?)?;
+    if reference(GPS_LONGITUDE_REF).as_deref() == Some("W") {
+        longitude = -longitude;
+    }
+    let altitude = find(entries, GPS_ALTITUDE)
+        .and_then(|e| tiff.decimal(e, 0))
+        .map(|altitude| {
+            // A reference of 1 means below sea level.
+            let below = find(entries, GPS_ALTITUDE_REF).and_then(|e| tiff.unsigned(e)) == Some(1);
+            if below {
+                -altitude
+            } else {
+                altitude
+            }
+        });
+
+    Some(Gps {
+        latitude,
+        longitude,
+        altitude,
+    })
+}
+
+/// Parses the EXIF tags we display from a JPEG or PNG file.
+pub fn parse(bytes: &[u8]) -> Option<Exif> {
+    let tiff = Tiff::new(find_exif(bytes)?)?;
+    let ifd0 = tiff.entries(tiff.first_ifd()?);
+    let sub_ifd = |tag| {
+        find(&ifd0, tag)
+            .and_then(|e| tiff.unsigned(e))
+            .map(|offset| tiff.entries(offset as usize))
+            .unwrap_or_default()
+    };
+    let exif_ifd = sub_ifd(EXIF_IFD);
+    let gps_ifd = sub_ifd(GPS_IFD);
+
+    let ascii = |entries: &[(u16, Entry)], tag| find(entries, tag).and_then(|e| tiff.ascii(e));
+
+    Some(Exif {
+        make: ascii(&ifd0, MAKE),
+        model: ascii(&ifd0, MODEL),
+        lens_make: ascii(&exif_ifd, LENS_MAKE),
+        lens_model: ascii(&exif_ifd, LENS_MODEL),
+        exposure_time: find(&exif_ifd, EXPOSURE_TIME).and_then(|e| tiff.rational(e, 0)),
+        f_number: find(&exif_ifd, F_NUMBER).and_then(|e| tiff.decimal(e, 0)),
+        iso: find(&exif_ifd, ISO).and_then(|e| tiff.unsigned(e)),
+        focal_length: find(&exif_ifd, FOCAL_LENGTH).and_then(|e| tiff.decimal(e, 0)),
+        date_taken: ascii(&exif_ifd, DATE_TIME_ORIGINAL).or_else(|| ascii(&ifd0, DATE_TIME)),
+        gps: parse_gps(&tiff, &gps_ifd),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn u16(value: u16, little_endian: bool) -> [u8; 2] {
+        if little_endian {
+            value.to_le_bytes()
+        } else {
+            value.to_be_bytes()
+        }
+    }
+
+    fn u32(value: u32, little_endian: bool) -> [u8; 4] {
+        if little_endian {
+            value.to_le_bytes()
+        } else {
+            value.to_be_bytes()
+        }
+    }
+
+    /// An IFD entry's value, for building test files.
+    enum Value {
+        Byte(u8),
+        Ascii(&'static str),
+        Short(u16),
+        Long(u32),
+        Rational(Vec<(u32, u32)>),
+        SRational(Vec<(i32, i32)>),
+    }
+    impl Value {
+        fn kind(&self) -> u16 {
+            match self {
+                Value::Byte(_) => 1,
+                Value::Ascii(_) => 2,
+                Value::Short(_) => 3,
+                Value::Long(_) => 4,
+                Value::Rational(_) => 5,
+                Value::SRational(_) => 10,
+            }
+        }
+
+        fn count(&self) -> u32 {
+            match self {
+                Value::Ascii(text) => text.len() as u32 + 1,
+                Value::Rational(values) => values.len() as u32,
+                Value::SRational(values) => values.len() as u32,
+                _ => 1,
+            }
+        }
+
+        fn bytes(&self, little_endian: bool) -> Vec<u8> {
+            match self {
+                Value::Byte(b) => vec![*b],
+                Value::Ascii(text) => [text.as_bytes(), b"\0"].concat(),
+                Value::Short(v) => u16(*v, little_endian).to_vec(),
+                Value::Long(v) => u32(*v, little_endian).to_vec(),
+                Value::Rational(values) => values
+                    .iter()
+                    .flat_map(|(n, d)| [u32(*n, little_endian), u32(*d, little_endian)].concat())
+                    .collect(),
+                Value::SRational(values) => values
+                    .iter()
+                    .flat_map(|(n, d)| {
+                        [u32(*n as u32, little_endian), u32(*d as u32, little_endian)].concat()
+                    })
+                    .collect(),
+            }
+        }
+    }
+
+    type Ifd = Vec<(u16, Value)>;
+
+    fn ifd_size(ifd: &Ifd) -> usize {
+        let data: usize = ifd
+            .iter()
+            .map(|(_, v)| v.bytes(true).len())
+            .filter(|len| *len > 4)
+            .sum();
+        2 + ifd.len() * 12 + 4 + data
+    }
+
+    /// Appends `ifd` with its out-of-line values straight after it.
+    fn write_ifd(out: &mut Vec<u8>, ifd: &Ifd, little_endian: bool) {
+        let mut data_offset = out.len() + 2 + ifd.len() * 12 + 4;
+        let mut data = Vec::new();
+
+        out.extend(u16(ifd.len() as u16, little_endian));
+        for (tag, value) in ifd {
+            out.extend(u16(*tag, little_endian));
+            out.extend(u16(value.kind(), little_endian));
+            out.extend(u32(value.count(), little_endian));
+            let mut bytes = value.bytes(little_endian);
+            if bytes.len() <= 4 {
+                bytes.resize(4, 0);
+                out.extend(bytes);
+            } else {
+                out.extend(u32(data_offset as u32, little_endian));
+                data_offset += bytes.len();
+                data.extend(bytes);
+            }
+        }
+        out.extend(u32(0, little_endian));
+        out.extend(data);
+    }
+
+    /// A TIFF block with IFD0 pointing at the given Exif and GPS IFDs.
+    fn tiff(little_endian: bool, mut ifd0: Ifd, exif: Ifd, gps: Ifd) -> Vec<u8> {
+        ifd0.push((EXIF_IFD, Value::Long(0)));
+        ifd0.push((GPS_IFD, Value::Long(0)));
+        let exif_offset = 8 + ifd_size(&ifd0);
+        let gps_offset = exif_offset + ifd_size(&exif);
+        let pointers = ifd0.len() - 2;
+        ifd0[pointers].1 = Value::Long(exif_offset as u32);
+        ifd0[pointers + 1].1 = Value::Long(gps_offset as u32);
+
+        let mut out = if little_endian {
+            vec![b'I', b'I', 42, 0, 8, 0, 0, 0]
+        } else {
+            vec![b'M', b'M', 0, 42, 0, 0, 0, 8]
+        };
+        for ifd in [&ifd0, &exif, &gps] {
+            write_ifd(&mut out, ifd, little_endian);
+        }
+        out
+    }
+
+    fn camera_tiff(little_endian: bool) -> Vec<u8> {
+        tiff(
+            little_endian,
+            vec![
+                (MAKE, Value::Ascii("Canon")),
+                (MODEL, Value::Ascii("Canon EOS 5D")),
+                (DATE_TIME, Value::Ascii("2023:06:02 09:00:00")),
+            ],
+            vec![
+                (EXPOSURE_TIME, Value::Rational(vec![(1, 250)])),
+                (F_NUMBER, Value::Rational(vec![(28, 10)])),
+                (ISO, Value::Short(400)),
+                (DATE_TIME_ORIGINAL, Value::Ascii("2023:06:01 12:00:00")),
+                (FOCAL_LENGTH, Value::Rational(vec![(50, 1)])),
+                (LENS_MODEL, Value::Ascii("EF50mm f/1.8")),
+            ],
+            vec![
+                (GPS_LATITUDE_REF, Value::Ascii("N")),
+                (
+                    GPS_LATITUDE,
+                    Value::Rational(vec![(51, 1), (30, 1), (0, 1)]),
+                ),
+                (GPS_LONGITUDE_REF, Value::Ascii("W")),
+                (
+                    GPS_LONGITUDE,
+                    Value::Rational(vec![(0, 1), (7, 1), (30, 1)]),
+                ),
+                (GPS_ALTITUDE_REF, Value::Byte(1)),
+                (GPS_ALTITUDE, Value::Rational(vec![(10, 1)])),
+            ],
+        )
+    }
+
+    fn jpeg(tiff: &[u8]) -> Vec<u8> {
+        let length = (2 + 6 + tiff.len()) as u16;
+        let mut out = vec![0xFF, 0xD8];
+        // An APP0 segment first, which should be skipped over.
+        out.extend([0xFF, 0xE0, 0, 4, 0, 0]);
+        out.extend([0xFF, 0xE1]);
+        out.extend(length.to_be_bytes());
+        out.extend(b"Exif\0\0");
+        out.extend(tiff);
+        out.extend([0xFF, 0xDA, 0, 2]);
+        out
+    }
+
+    fn png(tiff: &[u8]) -> Vec<u8> {
+        let mut out = PNG_SIGNATURE.to_vec();
+        for (kind, data) in [(b"IHDR", &[0u8; 13][..]), (b"eXIf", tiff), (b"IEND", &[])] {
+            out.extend((data.len() as u32).to_be_bytes());
+            out.extend(kind);
+            out.extend(data);
+            // The CRC isn't checked.
+            out.extend([0; 4]);
+        }
+        out
+    }
+
+    fn expected() -> Exif {
+        Exif {
+            make: Some("Canon".to_string()),
+            model: Some("Canon EOS 5D".to_string()),
+            lens_make: None,
+            lens_model: Some("EF50mm f/1.8".to_string()),
+            exposure_time: Some((1, 250)),
+            f_number: Some(2.8),
+            iso: Some(400),
+            focal_length: Some(50.0),
+            date_taken: Some("2023:06:01 12:00:00".to_string()),
+            gps: Some(Gps {
+                latitude: 51.5,
+                longitude: -0.125,
+                altitude: Some(-10.0),
+            }),
+        }
+    }
+
+    #[test]
+    fn reads_both_byte_orders() {
+        assert_eq!(parse(&jpeg(&camera_tiff(true))), Some(expected()));
+        assert_eq!(parse(&jpeg(&camera_tiff(false))), Some(expected()));
+    }
+
+    #[test]
+    fn reads_png_exif_chunks() {
+        assert_eq!(parse(&png(&camera_tiff(true))), Some(expected()));
+        assert_eq!(parse(&png(&camera_tiff(false))), Some(expected()));
+    }
+
+    #[test]
+    fn formats_values() {
+        let exif = expected();
+        assert_eq!(exif.camera().as_deref(), Some("Canon EOS 5D"));
+        assert_eq!(exif.lens().as_deref(), Some("EF50mm f/1.8"));
+        assert_eq!(exif.exposure().as_deref(), Some("1/250 s"));
+        assert_eq!(exif.aperture().as_deref(), Some("f/2.8"));
+        assert_eq!(exif.focal().as_deref(), Some("50 mm"));
+        assert_eq!(exif.date().as_deref(), Some("2023-06-01 12:00:00"));
+        assert_eq!(exif.gps.unwrap().format(), "51.50000° N, 0.12500° W");
+
+        let exposure = |time| {
+            Exif {
+                exposure_time: Some(time),
+                ..Exif::default()
+            }
+            .exposure()
+        };
+        assert_eq!(exposure((2, 1)).as_deref(), Some("2 s"));
+        assert_eq!(exposure((0, 1)), None);
+        assert_eq!(exposure((1, 0)), None);
+    }
+
+    #[test]
+    fn reads_signed_rationals_as_signed() {
+        let tiff = tiff(
+            false,
+            vec![],
+            vec![],
+            vec![
+                (
+                    GPS_LATITUDE,
+                    Value::SRational(vec![(10, 1), (0, 1), (0, 1)]),
+                ),
+                (
+                    GPS_LONGITUDE,
+                    Value::Rational(vec![(20, 1), (0, 1), (0, 1)]),
+                ),
+                (GPS_ALTITUDE, Value::SRational(vec![(-25, 2)])),
+            ],
+        );
+        let gps = parse(&jpeg(&tiff)).unwrap().gps.unwrap();
+        assert_eq!(gps.altitude, Some(-12.5));
+        assert_eq!((gps.latitude, gps.longitude), (10.0, 20.0));
+    }
+
+    #[test]
+    fn survives_truncated_files() {
+        for file in [jpeg(&camera_tiff(true)), png(&camera_tiff(false))] {
+            for end in 0..file.len() {
+                // Whatever it finds, it mustn't panic or read past the end.
+                let _ = parse(&file[..end]);
+            }
+        }
+        let tiff = camera_tiff(true);
+        for end in 0..tiff.len() {
+            let _ = parse(&jpeg(&tiff[..end]));
+        }
+        assert_eq!(parse(&jpeg(&camera_tiff(true))[..40]), None);
+    }
+
+    #[test]
+    fn ignores_impossible_lengths() {
+        let mut huge_chunk = PNG_SIGNATURE.to_vec();
+        huge_chunk.extend(u32::MAX.to_be_bytes());
+        huge_chunk.extend(b"tEXt");
+        assert_eq!(parse(&huge_chunk), None);
+
+        // A rational with a count so large its size overflows.
+        let tiff = tiff(
+            true,
+            vec![(MAKE, Value::Ascii("Canon"))],
+            vec![(F_NUMBER, Value::Long(0))],
+            vec![],
+        );
+        let mut file = jpeg(&tiff);
+        let entry = file
+            .windows(2)
+            .rposition(|w| w == F_NUMBER.to_le_bytes())
+            .unwrap();
+        file[entry + 2..entry + 4].copy_from_slice(&5u16.to_le_bytes());
+        file[entry + 4..entry + 8].copy_from_slice(&u32::MAX.to_le_bytes());
+        let exif = parse(&file).unwrap();
+        assert_eq!(exif.make.as_deref(), Some("Canon"));
+        assert_eq!(exif.f_number, None);
+    }
+
+    #[test]
+    fn ignores_files_without_exif() {
+        assert_eq!(parse(&[]), None);
+        assert_eq!(parse(b"GIF89a"), None);
+        assert_eq!(parse(&[0xFF, 0xD8, 0xFF, 0xDA, 0, 2]), None);
+        assert_eq!(parse(&jpeg(b"XX\0\x2a")), None);
+    }
+}
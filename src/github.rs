@@ -0,0 +1,310 @@
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use futures::future::LocalBoxFuture;
+use gloo::storage::{LocalStorage, Storage};
+use gloo_net::http::{Request, Response};
+use js_sys::Date;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::time_format::date_from_iso;
+use crate::GITHUB_API;
+
+const REPO_CACHE_KEY: &str = "github-repos";
+const README_CACHE_KEY: &str = "github-readmes";
+/// Stats are tiny, but READMEs can be big enough to fill local storage, so
+/// only a few of the smaller ones are kept.
+const REPO_CACHE_LIMITS: CacheLimits = CacheLimits {
+    entries: 64,
+    bytes: 4 * 1024,
+};
+const README_CACHE_LIMITS: CacheLimits = CacheLimits {
+    entries: 8,
+    bytes: 32 * 1024,
+};
+const RATE_LIMIT_KEY: &str = "github-rate-limit-reset";
+/// Unauthenticated requests get 60 an hour, so answers are reused for a while.
+const CACHE_MS: f64 = 6.0 * 60.0 * 60.0 * 1000.0;
+
+/// The parts of a repository we show next to a project.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct RepoStats {
+    #[serde(rename = "stargazers_count")]
+    pub stars: u32,
+    pub language: Option<String>,
+    /// ISO 8601 time of the last push, close enough to the last commit.
+    pub pushed_at: String,
+}
+impl RepoStats {
+    /// `pushed_at` in unix seconds.
+    pub fn pushed_timestamp(&self) -> u64 {
+        (date_from_iso(&self.pushed_at).get_time() / 1000.0) as u64
+    }
+}
+
+/// Anything that can look up a repository by "owner/name". The explorer takes
+/// one as a prop so it can be given canned stats instead of the real API.
+pub trait GitHubApi {
+    fn repo(&self, repo: &str) -> LocalBoxFuture<'static, Result<RepoStats, String>>;
+
+    /// The raw markdown of the repository's README.
+    fn readme(&self, repo: &str) -> LocalBoxFuture<'static, Result<String, String>>;
+}
+
+/// Talks to the GitHub REST API at `base`.
+pub struct RestClient {
+    base: String,
+}
+impl RestClient {
+    pub fn new(base: &str) -> RestClient {
+        RestClient {
+            base: base.trim_end_matches('/').to_string(),
+        }
+    }
+}
+
+/// GETs `url`, remembering when the rate limit runs out so we stop asking
+/// until it resets.
+async fn get(url: &str, accept: &str) -> Result<Response, String> {
+    let reset: Option<f64> = LocalStorage::get(RATE_LIMIT_KEY).ok();
+    if reset.is_some_and(|reset| reset > Date::now()) {
+        return Err("GitHub rate limit reached".to_string());
+    }
+
+    let resp = Request::get(url)
+        .header("Accept", accept)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    if resp.headers().get("x-ratelimit-remaining").as_deref() == Some("0") {
+        // The reset header is in unix seconds.
+        if let Some(reset) = resp
+            .headers()
+            .get("x-ratelimit-reset")
+            .and_then(|r| r.parse::<f64>().ok())
+        {
+            LocalStorage::set(RATE_LIMIT_KEY, reset * 1000.0).ok();
+        }
+    }
+    if !resp.ok() {
+        return Err(format!("{} {}", resp.status(), resp.status_text()));
+    }
+    Ok(resp)
+}
+
+impl GitHubApi for RestClient {
+    fn repo(&self, repo: &str) -> LocalBoxFuture<'static, Result<RepoStats, String>> {
+        let url = format!("{}/repos/{}", self.base, repo);
+        Box::pin(async move {
+            let resp = get(&url, "application/vnd.github+json").await?;
+            resp.json().await.map_err(|e| e.to_string())
+        })
+    }
+
+    fn readme(&self, repo: &str) -> LocalBoxFuture<'static, Result<String, String>> {
+        let url = format!("{}/repos/{}/readme", self.base, repo);
+        Box::pin(async move {
+            let resp = get(&url, "application/vnd.github.raw").await?;
+            resp.text().await.map_err(|e| e.to_string())
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+struct CacheEntry<T> {
+    value: T,
+    /// When it was fetched, in JS milliseconds.
+    fetched: f64,
+}
+
+/// How much a cache may keep: at most `entries` answers, none of them
+/// bigger than `bytes` once serialised.
+struct CacheLimits {
+    entries: usize,
+    bytes: usize,
+}
+
+fn load_cache<T: DeserializeOwned>(key: &str) -> HashMap<String, CacheEntry<T>> {
+    LocalStorage::get(key).unwrap_or_default()
+}
+
+/// Drops the oldest entries until there are at most `max`.
+fn evict_oldest<T>(cache: &mut HashMap<String, CacheEntry<T>>, max: usize) {
+    while cache.len() > max {
+        let oldest = cache
+            .iter()
+            .min_by(|a, b| a.1.fetched.total_cmp(&b.1.fetched))
+            .map(|(repo, _)| repo.clone());
+        match oldest {
+            Some(repo) => cache.remove(&repo),
+            None => break,
+        };
+    }
+}
+
+/// Answers from the `key` cache while fresh, otherwise from `request`,
+/// falling back to a stale entry if that fails.
+fn cached<T>(
+    key: &'static str,
+    limits: &'static CacheLimits,
+    repo: &str,
+    request: impl FnOnce() -> LocalBoxFuture<'static, Result<T, String>>,
+) -> LocalBoxFuture<'static, Result<T, String>>
+where
+    T: Serialize + DeserializeOwned + Clone + 'static,
+{
+    let cached = load_cache::<T>(key).remove(repo);
+    if let Some(entry) = &cached {
+        if Date::now() - entry.fetched < CACHE_MS {
+            let value = entry.value.clone();
+            return Box::pin(async move { Ok(value) });
+        }
+    }
+
+    let request = request();
+    let repo = repo.to_string();
+    Box::pin(async move {
+        match request.await {
+            Ok(value) => {
+                // Re-read in case other repos were cached meanwhile.
+                let mut cache = load_cache::<T>(key);
+                let entry = CacheEntry {
+                    value: value.clone(),
+                    fetched: Date::now(),
+                };
+                let size = serde_json::to_string(&entry).map_or(usize::MAX, |json| json.len());
+                if size <= limits.bytes {
+                    cache.insert(repo, entry);
+                } else {
+                    // Don't leave an outdated copy behind either.
+                    cache.remove(&repo);
+                }
+                evict_oldest(&mut cache, limits.entries);
+                LocalStorage::set(key, cache).ok();
+                Ok(value)
+            }
+            Err(e) => cached.map(|entry| entry.value).ok_or(e),
+        }
+    })
+}
+
+/// Keeps answers from `inner` in local storage so reloading the page doesn't
+/// spend the rate limit. Stale entries are still used if a refresh fails.
+pub struct CachedClient<C: GitHubApi> {
+    inner: C,
+}
+impl<C: GitHubApi> CachedClient<C> {
+    pub fn new(inner: C) -> CachedClient<C> {
+        CachedClient { inner }
+    }
+}
+impl<C: GitHubApi> GitHubApi for CachedClient<C> {
+    fn repo(&self, repo: &str) -> LocalBoxFuture<'static, Result<RepoStats, String>> {
+        cached(REPO_CACHE_KEY, &REPO_CACHE_LIMITS, repo, || {
+            self.inner.repo(repo)
+        })
+    }
+
+    fn readme(&self, repo: &str) -> LocalBoxFuture<'static, Result<String, String>> {
+        cached(README_CACHE_KEY, &README_CACHE_LIMITS, repo, || {
+            self.inner.readme(repo)
+        })
+    }
+}
+
+/// A shareable client for component props. Two are equal if they're the same
+/// client.
+#[derive(Clone)]
+pub struct GitHub(pub Rc<dyn GitHubApi>);
+impl PartialEq for GitHub {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+thread_local! {
+    /// Shared by every default `GitHub`, so props made with one compare equal.
+    static DEFAULT_CLIENT: GitHub =
+        GitHub(Rc::new(CachedClient::new(RestClient::new(GITHUB_API))));
+}
+impl Default for GitHub {
+    fn default() -> Self {
+        DEFAULT_CLIENT.with(GitHub::clone)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::executor::block_on;
+
+    use super::*;
+
+    /// Answers from canned stats and READMEs instead of the network.
+    #[derive(Default)]
+    struct FakeGitHub {
+        repos: HashMap<String, RepoStats>,
+        readmes: HashMap<String, String>,
+    }
+    impl GitHubApi for FakeGitHub {
+        fn repo(&self, repo: &str) -> LocalBoxFuture<'static, Result<RepoStats, String>> {
+            let found = self.repos.get(repo).cloned();
+            let repo = repo.to_string();
+            Box::pin(async move { found.ok_or(format!("404 Not Found: {}", repo)) })
+        }
+
+        fn readme(&self, repo: &str) -> LocalBoxFuture<'static, Result<String, String>> {
+            let found = self.readmes.get(repo).cloned();
+            Box::pin(async move { found.ok_or_else(|| "404 Not Found".to_string()) })
+        }
+    }
+
+    fn fake() -> FakeGitHub {
+        let stats = RepoStats {
+            stars: 12,
+            language: Some("Rust".to_string()),
+            pushed_at: "2024-05-01T12:00:00Z".to_string(),
+        };
+        FakeGitHub {
+            repos: HashMap::from([("14ROVI/website".to_string(), stats)]),
+            readmes: HashMap::from([("14ROVI/website".to_string(), "# Website".to_string())]),
+        }
+    }
+
+    #[test]
+    fn answers_through_the_shared_client() {
+        let github = GitHub(Rc::new(fake()));
+        let stats = block_on(github.0.repo("14ROVI/website")).unwrap();
+        assert_eq!(stats.stars, 12);
+        assert_eq!(stats.language.as_deref(), Some("Rust"));
+        assert_eq!(
+            block_on(github.0.readme("14ROVI/website")).as_deref(),
+            Ok("# Website")
+        );
+        assert!(block_on(github.0.repo("14ROVI/missing")).is_err());
+        assert!(block_on(github.0.readme("14ROVI/missing")).is_err());
+    }
+
+    #[test]
+    fn evicts_the_oldest_entries() {
+        let mut cache: HashMap<String, CacheEntry<u32>> = [("a", 3.0), ("b", 1.0), ("c", 2.0)]
+            .into_iter()
+            .map(|(repo, fetched)| (repo.to_string(), CacheEntry { value: 0, fetched }))
+            .collect();
+        evict_oldest(&mut cache, 3);
+        assert_eq!(cache.len(), 3);
+        evict_oldest(&mut cache, 2);
+        assert!(!cache.contains_key("b"));
+        evict_oldest(&mut cache, 0);
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn compares_by_client() {
+        let github = GitHub(Rc::new(fake()));
+        assert!(github == github.clone());
+        assert!(github != GitHub(Rc::new(fake())));
+        // Otherwise every render would make new props and render again.
+        assert!(GitHub::default() == GitHub::default());
+        assert!(GitHub::default() != github);
+    }
+}
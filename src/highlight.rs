@@ -0,0 +1,162 @@
+//! Just enough syntax highlighting for the code blocks in project READMEs:
+//! keywords, strings, comments and numbers for a handful of languages.
+
+/// What a highlighted run of code is, named after its CSS class.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    Plain,
+    Keyword,
+    String,
+    Comment,
+    Number,
+}
+impl Kind {
+    pub fn class(&self) -> Option<&'static str> {
+        match self {
+            Kind::Plain => None,
+            Kind::Keyword => Some("hl-keyword"),
+            Kind::String => Some("hl-string"),
+            Kind::Comment => Some("hl-comment"),
+            Kind::Number => Some("hl-number"),
+        }
+    }
+}
+
+struct Syntax {
+    /// Separated by whitespace.
+    keywords: &'static str,
+    line_comments: &'static [&'static str],
+    block_comment: Option<(&'static str, &'static str)>,
+    quotes: &'static [char],
+}
+
+const RUST: Syntax = Syntax {
+    keywords: "\
+        as async await break const continue crate dyn else enum extern false fn for if \
+        impl in let loop match mod move mut pub ref return self Self static struct super \
+        trait true type unsafe use where while",
+    line_comments: &["//"],
+    block_comment: Some(("/*", "*/")),
+    // Single quotes are lifetimes as often as characters.
+    quotes: &['"'],
+};
+
+const PYTHON: Syntax = Syntax {
+    keywords: "\
+        and as assert async await break class continue def del elif else except False \
+        finally for from global if import in is lambda None nonlocal not or pass raise \
+        return True try while with yield",
+    line_comments: &["#"],
+    block_comment: None,
+    quotes: &['"', '\''],
+};
+
+const JAVASCRIPT: Syntax = Syntax {
+    keywords: "\
+        async await break case catch class const continue default delete do else export \
+        extends false finally for from function if import in instanceof interface let new \
+        null return switch this throw true try type typeof undefined var while yield",
+    line_comments: &["//"],
+    block_comment: Some(("/*", "*/")),
+    quotes: &['"', '\'', '`'],
+};
+
+const SHELL: Syntax = Syntax {
+    keywords: "case do done echo elif else esac export fi for function if in then while",
+    line_comments: &["#"],
+    block_comment: None,
+    quotes: &['"', '\''],
+};
+
+const TOML: Syntax = Syntax {
+    keywords: "true false",
+    line_comments: &["#"],
+    block_comment: None,
+    quotes: &['"', '\''],
+};
+
+fn syntax(language: &str) -> Option<&'static Syntax> {
+    match language.to_ascii_lowercase().as_str() {
+        "rust" | "rs" => Some(&RUST),
+        "python" | "py" => Some(&PYTHON),
+        "javascript" | "js" | "typescript" | "ts" | "json" => Some(&JAVASCRIPT),
+        "bash" | "sh" | "shell" | "console" => Some(&SHELL),
+        "toml" | "ini" => Some(&TOML),
+        _ => None,
+    }
+}
+
+/// Splits `code` into runs of each [`Kind`]. Unknown languages come back as
+/// one plain run.
+pub fn highlight(language: &str, code: &str) -> Vec<(Kind, String)> {
+    let syntax = match syntax(language) {
+        Some(syntax) => syntax,
+        None => return vec![(Kind::Plain, code.to_string())],
+    };
+
+    let chars: Vec<char> = code.chars().collect();
+    let starts_with = |i: usize, pattern: &str| {
+        pattern
+            .chars()
+            .enumerate()
+            .all(|(offset, c)| chars.get(i + offset) == Some(&c))
+    };
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+
+    let mut runs: Vec<(Kind, String)> = vec![];
+    let mut push = |kind: Kind, text: &[char]| match runs.last_mut() {
+        Some((last, run)) if *last == kind => run.extend(text),
+        _ => runs.push((kind, text.iter().collect())),
+    };
+
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let start = i;
+
+        let kind = if syntax.line_comments.iter().any(|p| starts_with(i, p)) {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+            Kind::Comment
+        } else if let Some((open, close)) = syntax.block_comment.filter(|(o, _)| starts_with(i, o))
+        {
+            i += open.len();
+            while i < chars.len() && !starts_with(i, close) {
+                i += 1;
+            }
+            i = (i + close.len()).min(chars.len());
+            Kind::Comment
+        } else if syntax.quotes.contains(&c) {
+            i += 1;
+            while i < chars.len() && chars[i] != c {
+                // Skip whatever is escaped, including the quote.
+                i += if chars[i] == '\\' { 2 } else { 1 };
+            }
+            i = (i + 1).min(chars.len());
+            Kind::String
+        } else if c.is_ascii_digit() && (i == 0 || !is_word(chars[i - 1])) {
+            while i < chars.len() && (is_word(chars[i]) || chars[i] == '.') {
+                i += 1;
+            }
+            Kind::Number
+        } else if is_word(c) {
+            while i < chars.len() && is_word(chars[i]) {
+                i += 1;
+            }
+            let word: String = chars[start..i].iter().collect();
+            if syntax.keywords.split_whitespace().any(|k| k == word) {
+                Kind::Keyword
+            } else {
+                Kind::Plain
+            }
+        } else {
+            i += 1;
+            Kind::Plain
+        };
+
+        push(kind, &chars[start..i]);
+    }
+
+    runs
+}
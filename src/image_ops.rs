@@ -0,0 +1,368 @@
+//! Kodak Imaging style edits on raw RGBA pixels.
+//!
+//! Everything here works on plain byte buffers, as laid out by canvas
+//! `ImageData`, so it doesn't need a browser to run.
+
+/// An RGBA image, four bytes per pixel, rows top to bottom.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    pub width: u32,
+    pub height: u32,
+    pub data: Vec<u8>,
+}
+
+/// A rectangle in image pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Image {
+    pub fn new(width: u32, height: u32, data: Vec<u8>) -> Option<Image> {
+        (data.len() == width as usize * height as usize * 4).then_some(Image {
+            width,
+            height,
+            data,
+        })
+    }
+
+    fn index(&self, x: u32, y: u32) -> usize {
+        (y as usize * self.width as usize + x as usize) * 4
+    }
+
+    fn pixel(&self, x: u32, y: u32) -> [u8; 4] {
+        let i = self.index(x, y);
+        [
+            self.data[i],
+            self.data[i + 1],
+            self.data[i + 2],
+            self.data[i + 3],
+        ]
+    }
+
+    /// Builds a `width` by `height` image taking each pixel from `source(x, y)`.
+    fn from_fn(width: u32, height: u32, source: impl Fn(u32, u32) -> [u8; 4]) -> Image {
+        let mut data = Vec::with_capacity(width as usize * height as usize * 4);
+        for y in 0..height {
+            for x in 0..width {
+                data.extend_from_slice(&source(x, y));
+            }
+        }
+        Image {
+            width,
+            height,
+            data,
+        }
+    }
+
+    pub fn rotate_clockwise(&self) -> Image {
+        Image::from_fn(self.height, self.width, |x, y| {
+            self.pixel(y, self.height - 1 - x)
+        })
+    }
+
+    pub fn rotate_anticlockwise(&self) -> Image {
+        Image::from_fn(self.height, self.width, |x, y| {
+            self.pixel(self.width - 1 - y, x)
+        })
+    }
+
+    pub fn flip_horizontal(&self) -> Image {
+        Image::from_fn(self.width, self.height, |x, y| {
+            self.pixel(self.width - 1 - x, y)
+        })
+    }
+
+    pub fn flip_vertical(&self) -> Image {
+        Image::from_fn(self.width, self.height, |x, y| {
+            self.pixel(x, self.height - 1 - y)
+        })
+    }
+
+    /// Cuts out `rect`, clamped to the image. `None` if nothing is left.
+    pub fn crop(&self, rect: Rect) -> Option<Image> {
+        let x = rect.x.min(self.width);
+        let y = rect.y.min(self.height);
+        let width = rect.width.min(self.width - x);
+        let height = rect.height.min(self.height - y);
+        if width == 0 || height == 0 {
+            return None;
+        }
+        Some(Image::from_fn(width, height, |dx, dy| {
+            self.pixel(x + dx, y + dy)
+        }))
+    }
+
+    /// Applies `f` to the red, green and blue of every pixel.
+    fn map_rgb(&self, f: impl Fn(u8, u8, u8) -> [u8; 3]) -> Image {
+        let mut data = self.data.clone();
+        for pixel in data.chunks_exact_mut(4) {
+            let [r, g, b] = f(pixel[0], pixel[1], pixel[2]);
+            pixel[0] = r;
+            pixel[1] = g;
+            pixel[2] = b;
+        }
+        Image {
+            width: self.width,
+            height: self.height,
+            data,
+        }
+    }
+
+    /// `brightness` and `contrast` both run from -100 to 100, 0 leaves the
+    /// image as it is.
+    pub fn adjust(&self, brightness: i32, contrast: i32) -> Image {
+        if brightness == 0 && contrast == 0 {
+            return self.clone();
+        }
+        let offset = brightness.clamp(-100, 100) as f64 * 2.55;
+        let contrast = contrast.clamp(-100, 100) as f64 * 2.55;
+        // The usual contrast correction factor, pivoting around mid grey.
+        let factor = (259.0 * (contrast + 255.0)) / (255.0 * (259.0 - contrast));
+        let lookup: Vec<u8> = (0..=255)
+            .map(|v| {
+                (factor * (v as f64 - 128.0) + 128.0 + offset)
+                    .round()
+                    .clamp(0.0, 255.0) as u8
+            })
+            .collect();
+        self.map_rgb(|r, g, b| [lookup[r as usize], lookup[g as usize], lookup[b as usize]])
+    }
+
+    pub fn greyscale(&self) -> Image {
+        self.map_rgb(|r, g, b| {
+            let luma = (0.299 * r as f64 + 0.587 * g as f64 + 0.114 * b as f64).round() as u8;
+            [luma, luma, luma]
+        })
+    }
+
+    /// Floyd-Steinberg dithers the image down to `palette`.
+    pub fn dither(&self, palette: &[[u8; 3]]) -> Image {
+        if palette.is_empty() {
+            return self.clone();
+        }
+        // Searching the palette for every pixel is too slow for big photos, so
+        // look up the nearest colour to each 5-bit-per-channel bucket once.
+        let buckets: Vec<[u8; 3]> = (0..32 * 32 * 32)
+            .map(|i: i32| {
+                let bucket = [i >> 10, (i >> 5) & 31, i & 31];
+                nearest(palette, bucket.map(|c| c * 8 + 4))
+            })
+            .collect();
+        let lookup =
+            |c: [i32; 3]| buckets[((c[0] >> 3) << 10 | (c[1] >> 3) << 5 | c[2] >> 3) as usize];
+
+        let width = self.width as usize;
+        let mut data = self.data.clone();
+        // Carried error for this row and the next, per channel.
+        let mut current = vec![[0i32; 3]; width + 2];
+        let mut next = vec![[0i32; 3]; width + 2];
+
+        for y in 0..self.height as usize {
+            for x in 0..width {
+                let i = (y * width + x) * 4;
+                let wanted =
+                    [0, 1, 2].map(|c| (data[i + c] as i32 + current[x + 1][c]).clamp(0, 255));
+                let chosen = lookup(wanted);
+                data[i..i + 3].copy_from_slice(&chosen);
+                for c in 0..3 {
+                    let error = wanted[c] - chosen[c] as i32;
+                    current[x + 2][c] += error * 7 / 16;
+                    next[x][c] += error * 3 / 16;
+                    next[x + 1][c] += error * 5 / 16;
+                    next[x + 2][c] += error / 16;
+                }
+            }
+            std::mem::swap(&mut current, &mut next);
+            next.iter_mut().for_each(|e| *e = [0; 3]);
+        }
+
+        Image {
+            width: self.width,
+            height: self.height,
+            data,
+        }
+    }
+}
+
+fn nearest(palette: &[[u8; 3]], colour: [i32; 3]) -> [u8; 3] {
+    *palette
+        .iter()
+        .min_by_key(|p| {
+            (0..3)
+                .map(|c| (p[c] as i32 - colour[c]).pow(2))
+                .sum::<i32>()
+        })
+        .unwrap()
+}
+
+/// The 256 colours of Windows' halftone palette: the 20 static system colours,
+/// a 6x6x6 colour cube and a ramp of greys to fill the rest.
+pub fn win98_palette() -> Vec<[u8; 3]> {
+    let mut palette = vec![
+        [0, 0, 0],
+        [128, 0, 0],
+        [0, 128, 0],
+        [128, 128, 0],
+        [0, 0, 128],
+        [128, 0, 128],
+        [0, 128, 128],
+        [192, 192, 192],
+        [192, 220, 192],
+        [166, 202, 240],
+        [255, 251, 240],
+        [160, 160, 164],
+        [128, 128, 128],
+        [255, 0, 0],
+        [0, 255, 0],
+        [255, 255, 0],
+        [0, 0, 255],
+        [255, 0, 255],
+        [0, 255, 255],
+        [255, 255, 255],
+    ];
+    for r in 0..6 {
+        for g in 0..6 {
+            for b in 0..6 {
+                palette.push([r * 51, g * 51, b * 51]);
+            }
+        }
+    }
+    let greys = 256 - palette.len();
+    for i in 0..greys {
+        let grey = ((i + 1) * 255 / (greys + 1)) as u8;
+        palette.push([grey, grey, grey]);
+    }
+    palette
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A `width` by `height` image whose red channel numbers the pixels in
+    /// reading order, so moves are easy to follow.
+    fn numbered(width: u32, height: u32) -> Image {
+        let data = (0..width * height)
+            .flat_map(|i| [i as u8, 0, 0, 255])
+            .collect();
+        Image::new(width, height, data).unwrap()
+    }
+
+    fn reds(image: &Image) -> Vec<u8> {
+        image.data.chunks_exact(4).map(|p| p[0]).collect()
+    }
+
+    #[test]
+    fn new_checks_the_buffer_length() {
+        assert!(Image::new(2, 2, vec![0; 16]).is_some());
+        assert!(Image::new(2, 2, vec![0; 15]).is_none());
+        assert!(Image::new(0, 0, vec![]).is_some());
+    }
+
+    #[test]
+    fn rotates_odd_sizes() {
+        // 0 1 2
+        // 3 4 5
+        let image = numbered(3, 2);
+
+        let clockwise = image.rotate_clockwise();
+        assert_eq!((clockwise.width, clockwise.height), (2, 3));
+        assert_eq!(reds(&clockwise), [3, 0, 4, 1, 5, 2]);
+
+        let anticlockwise = image.rotate_anticlockwise();
+        assert_eq!((anticlockwise.width, anticlockwise.height), (2, 3));
+        assert_eq!(reds(&anticlockwise), [2, 5, 1, 4, 0, 3]);
+
+        assert_eq!(clockwise.rotate_anticlockwise(), image);
+        assert_eq!(
+            image
+                .rotate_clockwise()
+                .rotate_clockwise()
+                .rotate_clockwise()
+                .rotate_clockwise(),
+            image
+        );
+    }
+
+    #[test]
+    fn flips() {
+        let image = numbered(3, 2);
+        assert_eq!(reds(&image.flip_horizontal()), [2, 1, 0, 5, 4, 3]);
+        assert_eq!(reds(&image.flip_vertical()), [3, 4, 5, 0, 1, 2]);
+        assert_eq!(image.flip_horizontal().flip_horizontal(), image);
+    }
+
+    #[test]
+    fn crops_and_clamps_to_the_image() {
+        let image = numbered(3, 3);
+        let rect = |x, y, width, height| Rect {
+            x,
+            y,
+            width,
+            height,
+        };
+
+        let middle = image.crop(rect(1, 1, 1, 1)).unwrap();
+        assert_eq!((middle.width, middle.height), (1, 1));
+        assert_eq!(reds(&middle), [4]);
+
+        let overhanging = image.crop(rect(1, 2, 10, 10)).unwrap();
+        assert_eq!((overhanging.width, overhanging.height), (2, 1));
+        assert_eq!(reds(&overhanging), [7, 8]);
+
+        assert_eq!(image.crop(rect(0, 0, 0, 2)), None);
+        assert_eq!(image.crop(rect(3, 3, 2, 2)), None);
+        assert_eq!(numbered(0, 0).crop(rect(0, 0, 1, 1)), None);
+    }
+
+    #[test]
+    fn adjusts_colour_but_not_alpha() {
+        let image = Image::new(1, 2, vec![100, 128, 200, 50, 0, 255, 10, 255]).unwrap();
+        assert_eq!(image.adjust(0, 0), image);
+
+        let brighter = image.adjust(100, 0);
+        assert_eq!(brighter.data, [255, 255, 255, 50, 255, 255, 255, 255]);
+
+        let darker = image.adjust(-10, 0);
+        assert_eq!(darker.data, [75, 103, 175, 50, 0, 230, 0, 255]);
+
+        // Full contrast pushes everything away from mid grey.
+        let contrast = image.adjust(0, 100);
+        assert_eq!(contrast.data, [0, 128, 255, 50, 0, 255, 0, 255]);
+    }
+
+    #[test]
+    fn dithers_to_the_palette() {
+        let palette = [[0, 0, 0], [255, 255, 255]];
+        let grey = Image::new(5, 3, [128, 128, 128, 255].repeat(15)).unwrap();
+        let dithered = grey.dither(&palette);
+
+        assert_eq!((dithered.width, dithered.height), (5, 3));
+        for pixel in dithered.data.chunks_exact(4) {
+            assert!(palette.contains(&[pixel[0], pixel[1], pixel[2]]));
+            assert_eq!(pixel[3], 255);
+        }
+        // Mid grey comes out as a roughly even mix.
+        let white = dithered
+            .data
+            .chunks_exact(4)
+            .filter(|p| p[0] == 255)
+            .count();
+        assert!((6..=9).contains(&white), "{} white pixels", white);
+
+        // Colours already in the palette are left alone.
+        let black = Image::new(2, 1, [0, 0, 0, 255].repeat(2)).unwrap();
+        assert_eq!(black.dither(&palette), black);
+        assert_eq!(numbered(0, 0).dither(&palette), numbered(0, 0));
+        assert_eq!(grey.dither(&[]), grey);
+    }
+
+    #[test]
+    fn the_win98_palette_has_256_colours() {
+        assert_eq!(win98_palette().len(), 256);
+    }
+}
@@ -0,0 +1,228 @@
+use futures::channel::mpsc::{self, UnboundedSender};
+use futures::future::{abortable, AbortHandle};
+use futures::{SinkExt, StreamExt};
+use gloo::net::websocket::{futures::WebSocket, Message as WsMessage};
+use gloo::timers::callback::Interval;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use wasm_bindgen_futures::spawn_local;
+use yew::Callback;
+
+const LANYARD_SOCKET: &str = "wss://api.lanyard.rest/socket";
+
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+pub struct Timestamps {
+    pub start: Option<u64>,
+    pub end: Option<u64>,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+pub struct DiscordUser {
+    #[serde(default)]
+    pub id: String,
+    #[serde(default)]
+    pub username: String,
+    pub global_name: Option<String>,
+    pub avatar: Option<String>,
+}
+impl DiscordUser {
+    pub fn display_name(&self) -> &str {
+        self.global_name.as_deref().unwrap_or(&self.username)
+    }
+
+    pub fn avatar_url(&self) -> Option<String> {
+        self.avatar.as_ref().map(|avatar| {
+            format!(
+                "https://cdn.discordapp.com/avatars/{}/{}.png?size=128",
+                self.id, avatar
+            )
+        })
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+pub struct Emoji {
+    pub name: String,
+    pub id: Option<String>,
+    #[serde(default)]
+    pub animated: bool,
+}
+impl Emoji {
+    /// Custom emoji are images, standard ones are just their unicode name.
+    pub fn image_url(&self) -> Option<String> {
+        let extension = if self.animated { "gif" } else { "png" };
+        self.id
+            .as_ref()
+            .map(|id| format!("https://cdn.discordapp.com/emojis/{}.{}", id, extension))
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+pub struct Assets {
+    pub large_image: Option<String>,
+    pub large_text: Option<String>,
+    pub small_image: Option<String>,
+    pub small_text: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+pub struct Activity {
+    pub name: String,
+    #[serde(rename = "type", default)]
+    pub kind: u8,
+    pub state: Option<String>,
+    pub details: Option<String>,
+    pub application_id: Option<String>,
+    pub timestamps: Option<Timestamps>,
+    pub assets: Option<Assets>,
+    pub emoji: Option<Emoji>,
+}
+impl Activity {
+    /// Discord's activity type for "Playing".
+    pub const PLAYING: u8 = 0;
+    /// Discord's activity type for "Streaming".
+    pub const STREAMING: u8 = 1;
+    /// Discord's activity type for "Listening to".
+    pub const LISTENING: u8 = 2;
+    /// Discord's activity type for "Watching".
+    pub const WATCHING: u8 = 3;
+    /// Discord's activity type for a user's custom status.
+    pub const CUSTOM_STATUS: u8 = 4;
+    /// Discord's activity type for "Competing in".
+    pub const COMPETING: u8 = 5;
+
+    /// Resolves an activity asset key into an image URL.
+    pub fn asset_url(&self, asset: &str) -> Option<String> {
+        if let Some(external) = asset.strip_prefix("mp:") {
+            Some(format!("https://media.discordapp.net/{}", external))
+        } else if let Some(spotify) = asset.strip_prefix("spotify:") {
+            Some(format!("https://i.scdn.co/image/{}", spotify))
+        } else {
+            self.application_id.as_ref().map(|application_id| {
+                format!(
+                    "https://cdn.discordapp.com/app-assets/{}/{}.png",
+                    application_id, asset
+                )
+            })
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+pub struct SpotifyPresence {
+    #[serde(default)]
+    pub album_art_url: String,
+    #[serde(default)]
+    pub song: String,
+    #[serde(default)]
+    pub album: String,
+    #[serde(default)]
+    pub artist: String,
+    #[serde(default)]
+    pub timestamps: Timestamps,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+pub struct Presence {
+    #[serde(default)]
+    pub discord_user: DiscordUser,
+    #[serde(default)]
+    pub discord_status: String,
+    #[serde(default)]
+    pub activities: Vec<Activity>,
+    #[serde(default)]
+    pub listening_to_spotify: bool,
+    pub spotify: Option<SpotifyPresence>,
+}
+
+#[derive(Deserialize)]
+struct LanyardMessage {
+    op: u8,
+    #[serde(default)]
+    d: Value,
+}
+
+/// Keeps a Lanyard subscription alive, closing the socket when dropped.
+pub struct LanyardConnection {
+    reader: AbortHandle,
+    _writer: UnboundedSender<String>,
+}
+impl Drop for LanyardConnection {
+    fn drop(&mut self) {
+        self.reader.abort();
+    }
+}
+
+/// Subscribes to a Discord user's presence through Lanyard's websocket.
+///
+/// `on_presence` is called with the initial state and every update after it.
+pub fn connect(user_id: &str, on_presence: Callback<Presence>) -> Option<LanyardConnection> {
+    let ws = WebSocket::open(LANYARD_SOCKET)
+        .map_err(|e| log::error!("Couldn't connect to Lanyard ws: {:?}", e))
+        .ok()?;
+    let (mut write, mut read) = ws.split();
+    let (tx, mut rx) = mpsc::unbounded::<String>();
+
+    // TODO: reconnection handling incase of error
+
+    spawn_local(async move {
+        while let Some(msg) = rx.next().await {
+            log::info!("sent {}", &msg);
+            if write.send(WsMessage::Text(msg)).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    let subscribe = json!({ "op": 2, "d": { "subscribe_to_id": user_id } }).to_string();
+    let sender = tx.clone();
+    let (reader, reader_handle) = abortable(async move {
+        let mut _heartbeat = None;
+
+        while let Some(Ok(msg)) = read.next().await {
+            let text = match msg {
+                WsMessage::Text(text) => text,
+                WsMessage::Bytes(_) => continue,
+            };
+            let message: LanyardMessage = match serde_json::from_str(&text) {
+                Ok(message) => message,
+                Err(e) => {
+                    log::error!("Bad Lanyard message: {}", e);
+                    continue;
+                }
+            };
+
+            if message.op == 1 {
+                log::info!("gotta start heartbeat");
+                let heartbeat_duration = message.d["heartbeat_interval"]
+                    .as_u64()
+                    .unwrap_or(30_000);
+
+                let mut tx = sender.clone();
+                tx.send(subscribe.clone()).await.ok();
+
+                let tx = sender.clone();
+                _heartbeat = Some(Interval::new(heartbeat_duration as u32, move || {
+                    let mut tx = tx.clone();
+                    spawn_local(async move {
+                        tx.send(r#"{ "op": 3 }"#.to_string()).await.unwrap_or(());
+                    });
+                }));
+            } else {
+                match serde_json::from_value::<Presence>(message.d) {
+                    Ok(presence) => on_presence.emit(presence),
+                    Err(e) => log::error!("Bad Lanyard presence: {}", e),
+                }
+            }
+        }
+        log::info!("Lanyard ws closed.");
+    });
+    spawn_local(async move {
+        reader.await.ok();
+    });
+
+    Some(LanyardConnection {
+        reader: reader_handle,
+        _writer: tx,
+    })
+}
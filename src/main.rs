@@ -1,4 +1,15 @@
+mod backgrounds;
+mod colour;
 mod copland;
+mod exif;
+mod github;
+mod highlight;
+mod image_ops;
+mod lanyard;
+mod markdown;
+mod schemes;
+mod screensavers;
+mod time_format;
 mod window;
 mod windows;
 
@@ -6,9 +17,16 @@ use copland::{Copland, Theme};
 use serde::Deserialize;
 use yew::prelude::*;
 
-use rand::Rng;
-
-pub const MAX_BACKGROUND_INDEX: u32 = 23;
+/// Discord account whose presence is shown via Lanyard.
+pub const DISCORD_USER_ID: &str = "195512978634833920";
+/// Letterboxd RSS feed used when the films API is down. Letterboxd doesn't
+/// send CORS headers so this is a bundled copy by default, but it can point at
+/// any proxied `https://letterboxd.com/<user>/rss/` URL.
+pub const LETTERBOXD_FEED: &str = "assets/letterboxd.xml";
+/// lrclib compatible endpoint used to look up time-synced lyrics.
+pub const LYRICS_ENDPOINT: &str = "https://lrclib.net/api/get";
+/// GitHub REST API used for project stars, languages and last commits.
+pub const GITHUB_API: &str = "https://api.github.com";
 
 #[derive(Deserialize)]
 pub struct NoteJson {
@@ -21,10 +39,7 @@ pub struct NoteJson {
 
 #[function_component(App)]
 fn app() -> Html {
-    let mut rng = rand::thread_rng();
-    let background = rng.gen_range(1..MAX_BACKGROUND_INDEX);
-
-    let theme = use_reducer(|| Theme { background });
+    let theme = use_reducer(Theme::load);
 
     html! {
         <ContextProvider<UseReducerHandle<Theme>> context={theme}>
@@ -0,0 +1,212 @@
+//! Renders markdown straight to Yew nodes rather than an HTML string, so there
+//! is no `innerHTML` and raw HTML in the source is left out.
+
+use pulldown_cmark::{CodeBlockKind, Event, LinkType, Options, Parser, Tag};
+use yew::virtual_dom::{VList, VNode, VTag, VText};
+use yew::Html;
+
+use crate::highlight;
+
+/// Where relative URLs in a document point, e.g. into a README's repository.
+pub struct Base {
+    pub links: String,
+    pub images: String,
+}
+
+/// Resolves `url` against `base` unless it's already absolute or a fragment.
+fn resolve(url: &str, base: Option<&str>) -> String {
+    let base = match base {
+        Some(base) => base.trim_end_matches('/'),
+        None => return url.to_string(),
+    };
+    if url.contains("://") || url.starts_with('#') || url.starts_with("mailto:") {
+        return url.to_string();
+    }
+    let path = url.trim_start_matches("./").trim_start_matches('/');
+    format!("{}/{}", base, path)
+}
+
+fn element(tag: &Tag, base: Option<&Base>, in_head: bool) -> VTag {
+    match tag {
+        Tag::Paragraph => VTag::new("p"),
+        Tag::Heading(level, ..) => VTag::new(level.to_string()),
+        Tag::BlockQuote => VTag::new("blockquote"),
+        Tag::CodeBlock(_) => VTag::new("pre"),
+        Tag::List(Some(start)) => {
+            let mut list = VTag::new("ol");
+            if *start != 1 {
+                list.add_attribute("start", start.to_string());
+            }
+            list
+        }
+        Tag::List(None) => VTag::new("ul"),
+        Tag::Item => VTag::new("li"),
+        Tag::Table(_) => VTag::new("table"),
+        Tag::TableHead => VTag::new("thead"),
+        Tag::TableRow => VTag::new("tr"),
+        Tag::TableCell if in_head => VTag::new("th"),
+        Tag::TableCell => VTag::new("td"),
+        Tag::Emphasis => VTag::new("em"),
+        Tag::Strong => VTag::new("strong"),
+        Tag::Strikethrough => VTag::new("s"),
+        Tag::Link(kind, url, title) => {
+            let mut link = VTag::new("a");
+            if *kind == LinkType::Email {
+                link.add_attribute("href", format!("mailto:{}", url));
+            } else if !url
+                .trim_start()
+                .to_ascii_lowercase()
+                .starts_with("javascript:")
+            {
+                let links = base.map(|b| b.links.as_str());
+                link.add_attribute("href", resolve(url, links));
+            }
+            if !title.is_empty() {
+                link.add_attribute("title", title.to_string());
+            }
+            link.add_attribute("target", "_blank");
+            link.add_attribute("rel", "noopener noreferrer");
+            link
+        }
+        Tag::Image(_, url, title) => {
+            let mut image = VTag::new("img");
+            let images = base.map(|b| b.images.as_str());
+            image.add_attribute("src", resolve(url, images));
+            image.add_attribute("loading", "lazy");
+            if !title.is_empty() {
+                image.add_attribute("title", title.to_string());
+            }
+            image
+        }
+        // Footnotes aren't enabled.
+        Tag::FootnoteDefinition(_) => VTag::new("div"),
+    }
+}
+
+fn is_image(event: &Event) -> bool {
+    matches!(
+        event,
+        Event::Start(Tag::Image(..)) | Event::End(Tag::Image(..))
+    )
+}
+
+/// A highlighted `<code>` for the inside of a code block's `<pre>`.
+fn code_block(language: &str, code: &str) -> VNode {
+    let mut tag = VTag::new("code");
+    if !language.is_empty() {
+        tag.add_attribute("class", format!("language-{}", language));
+    }
+    for (kind, text) in highlight::highlight(language, code) {
+        let text: VNode = VText::new(text).into();
+        match kind.class() {
+            Some(class) => {
+                let mut span = VTag::new("span");
+                span.add_attribute("class", class);
+                span.add_child(text);
+                tag.add_child(span.into());
+            }
+            None => tag.add_child(text),
+        }
+    }
+    tag.into()
+}
+
+/// Parses `source` as CommonMark with strikethrough, tables and task lists.
+pub fn render(source: &str) -> Html {
+    render_with(source, None)
+}
+
+/// Like [`render`], with relative links and images resolved against `base`.
+pub fn render_relative_to(source: &str, base: &Base) -> Html {
+    render_with(source, Some(base))
+}
+
+fn render_with(source: &str, base: Option<&Base>) -> Html {
+    let options =
+        Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TABLES | Options::ENABLE_TASKLISTS;
+    let mut root = VList::new();
+    // Elements that are still open, innermost last.
+    let mut open: Vec<VTag> = vec![];
+    // Alt text collected while inside an image.
+    let mut alt: Option<String> = None;
+    // Language and text of the code block we're inside.
+    let mut code: Option<(String, String)> = None;
+    let mut in_head = false;
+
+    fn push(open: &mut [VTag], root: &mut VList, node: VNode) {
+        match open.last_mut() {
+            Some(parent) => parent.add_child(node),
+            None => root.add_child(node),
+        }
+    }
+
+    for event in Parser::new_ext(source, options) {
+        match event {
+            // Only the text of anything inside an image is kept, as its alt.
+            Event::Start(_) | Event::End(_) if alt.is_some() && !is_image(&event) => {}
+            Event::Start(tag) => {
+                match &tag {
+                    Tag::Image(..) => alt = Some(String::new()),
+                    Tag::CodeBlock(kind) => {
+                        let language = match kind {
+                            CodeBlockKind::Fenced(info) => {
+                                info.split_whitespace().next().unwrap_or_default()
+                            }
+                            CodeBlockKind::Indented => "",
+                        };
+                        code = Some((language.to_string(), String::new()));
+                    }
+                    _ => {}
+                }
+                open.push(element(&tag, base, in_head));
+                // The header cells aren't wrapped in a row of their own.
+                if let Tag::TableHead = tag {
+                    in_head = true;
+                    open.push(VTag::new("tr"));
+                }
+            }
+            Event::End(tag) => {
+                if let Tag::TableHead = tag {
+                    in_head = false;
+                    if let Some(row) = open.pop() {
+                        push(&mut open, &mut root, row.into());
+                    }
+                }
+                let mut element = match open.pop() {
+                    Some(element) => element,
+                    None => continue,
+                };
+                if let Tag::Image(..) = tag {
+                    element.add_attribute("alt", alt.take().unwrap_or_default());
+                }
+                if let Some((language, text)) = code.take().filter(|_| element.tag() == "pre") {
+                    element.add_child(code_block(&language, &text));
+                }
+                push(&mut open, &mut root, element.into());
+            }
+            Event::Text(text) => match (&mut alt, &mut code) {
+                (Some(alt), _) => alt.push_str(&text),
+                (None, Some((_, code))) => code.push_str(&text),
+                (None, None) => push(&mut open, &mut root, VText::new(text.to_string()).into()),
+            },
+            Event::Code(code) => {
+                let mut tag = VTag::new("code");
+                tag.add_child(VText::new(code.to_string()).into());
+                push(&mut open, &mut root, tag.into());
+            }
+            Event::SoftBreak => push(&mut open, &mut root, VText::new(" ").into()),
+            Event::HardBreak => push(&mut open, &mut root, VTag::new("br").into()),
+            Event::Rule => push(&mut open, &mut root, VTag::new("hr").into()),
+            Event::TaskListMarker(checked) => {
+                let mut input = VTag::new("input");
+                input.add_attribute("type", "checkbox");
+                input.add_attribute("disabled", "");
+                input.set_checked(checked);
+                push(&mut open, &mut root, input.into());
+            }
+            Event::Html(_) | Event::FootnoteReference(_) => {}
+        }
+    }
+
+    root.into()
+}
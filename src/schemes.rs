@@ -0,0 +1,204 @@
+//! Appearance schemes for the window chrome. 98.css only knows Windows
+//! Standard, so style.css restates its colours in terms of the custom
+//! properties a [`Scheme`] sets on `.copland`.
+
+use gloo::storage::{LocalStorage, Storage};
+use gloo::utils::window;
+
+const SCHEME_KEY: &str = "scheme";
+
+/// A named set of colours and font sizes, like the ones in Win98's
+/// Appearance tab. Colours are anything CSS accepts.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Scheme {
+    /// Stored in LocalStorage, so don't rename these.
+    pub id: &'static str,
+    pub name: &'static str,
+    pub desktop: &'static str,
+    /// Buttons, windows and the taskbar.
+    pub face: &'static str,
+    /// The four edges of a raised face, from the outside light edge round to
+    /// the outside dark one.
+    pub highlight: &'static str,
+    pub light: &'static str,
+    pub shadow: &'static str,
+    pub dark_shadow: &'static str,
+    pub text: &'static str,
+    /// Inside text boxes, lists and other sunken panels.
+    pub window: &'static str,
+    pub window_text: &'static str,
+    pub selection: &'static str,
+    pub selection_text: &'static str,
+    pub title: (&'static str, &'static str),
+    pub title_text: &'static str,
+    pub inactive_title: (&'static str, &'static str),
+    pub inactive_title_text: &'static str,
+    /// In pixels.
+    pub font_size: u8,
+    pub title_font_size: u8,
+}
+
+pub static SCHEMES: [Scheme; 5] = [
+    Scheme {
+        id: "standard",
+        name: "Windows Standard",
+        desktop: "#008080",
+        face: "silver",
+        highlight: "#fff",
+        light: "#dfdfdf",
+        shadow: "grey",
+        dark_shadow: "#0a0a0a",
+        text: "#222",
+        window: "#fff",
+        window_text: "#000",
+        selection: "navy",
+        selection_text: "#fff",
+        title: ("navy", "#1084d0"),
+        title_text: "#fff",
+        inactive_title: ("grey", "#b5b5b5"),
+        inactive_title_text: "#fff",
+        font_size: 11,
+        title_font_size: 11,
+    },
+    Scheme {
+        id: "high-contrast",
+        name: "High Contrast Black (large)",
+        desktop: "#000",
+        face: "#000",
+        highlight: "#fff",
+        light: "#000",
+        shadow: "#000",
+        dark_shadow: "#fff",
+        text: "#fff",
+        window: "#000",
+        window_text: "#fff",
+        selection: "#800080",
+        selection_text: "#fff",
+        title: ("#800080", "#800080"),
+        title_text: "#fff",
+        inactive_title: ("#008000", "#008000"),
+        inactive_title_text: "#fff",
+        font_size: 14,
+        title_font_size: 14,
+    },
+    Scheme {
+        id: "rainy-day",
+        name: "Rainy Day",
+        desktop: "#000",
+        face: "#8199b3",
+        highlight: "#c6d1dd",
+        light: "#9fb2c6",
+        shadow: "#4f657d",
+        dark_shadow: "#000",
+        text: "#000",
+        window: "#fff",
+        window_text: "#000",
+        selection: "#4f657d",
+        selection_text: "#fff",
+        title: ("#4f657d", "#9db9eb"),
+        title_text: "#fff",
+        inactive_title: ("#808080", "#c0c0c0"),
+        inactive_title_text: "#c0c0c0",
+        font_size: 11,
+        title_font_size: 11,
+    },
+    Scheme {
+        id: "brick",
+        name: "Brick",
+        desktop: "#400000",
+        face: "#c2bfa5",
+        highlight: "#e1e0d2",
+        light: "#d1cfbe",
+        shadow: "#8d8961",
+        dark_shadow: "#000",
+        text: "#000",
+        window: "#fff",
+        window_text: "#000",
+        selection: "#800000",
+        selection_text: "#fff",
+        title: ("#800000", "#c08060"),
+        title_text: "#fff",
+        inactive_title: ("#6a6a4f", "#a6a284"),
+        inactive_title_text: "#c2bfa5",
+        font_size: 11,
+        title_font_size: 12,
+    },
+    Scheme {
+        id: "dark",
+        name: "Dark",
+        desktop: "#101418",
+        face: "#2d2d30",
+        highlight: "#5a5a5e",
+        light: "#3e3e42",
+        shadow: "#1b1b1c",
+        dark_shadow: "#000",
+        text: "#e0e0e0",
+        window: "#1e1e1e",
+        window_text: "#e0e0e0",
+        selection: "#264f78",
+        selection_text: "#fff",
+        title: ("#1f2a44", "#3a5fa0"),
+        title_text: "#fff",
+        inactive_title: ("#3a3a3a", "#555"),
+        inactive_title_text: "#aaa",
+        font_size: 11,
+        title_font_size: 11,
+    },
+];
+
+impl Scheme {
+    pub fn by_id(id: &str) -> Option<&'static Scheme> {
+        SCHEMES.iter().find(|scheme| scheme.id == id)
+    }
+
+    /// The visitor's saved scheme, or whichever suits their system's light or
+    /// dark preference if they haven't picked one.
+    pub fn load() -> &'static Scheme {
+        let saved = LocalStorage::get::<String>(SCHEME_KEY).ok();
+        let dark = || {
+            window()
+                .match_media("(prefers-color-scheme: dark)")
+                .ok()
+                .flatten()
+                .is_some_and(|query| query.matches())
+        };
+        saved
+            .and_then(|id| Scheme::by_id(&id))
+            .or_else(|| Scheme::by_id(if dark() { "dark" } else { "standard" }))
+            .unwrap_or(&SCHEMES[0])
+    }
+
+    pub fn save(&self) {
+        LocalStorage::set(SCHEME_KEY, self.id).ok();
+    }
+
+    /// CSS custom properties consumed by the colour scheme rules in style.css.
+    pub fn css_variables(&self) -> String {
+        format!(
+            "--desktop: {}; --face: {}; --highlight: {}; --light: {}; --shadow: {}; \
+             --dark-shadow: {}; --text: {}; --window: {}; --window-text: {}; \
+             --selection: {}; --selection-text: {}; --title-start: {}; --title-end: {}; \
+             --title-text: {}; --inactive-title-start: {}; --inactive-title-end: {}; \
+             --inactive-title-text: {}; --font-size: {}px; --title-font-size: {}px;",
+            self.desktop,
+            self.face,
+            self.highlight,
+            self.light,
+            self.shadow,
+            self.dark_shadow,
+            self.text,
+            self.window,
+            self.window_text,
+            self.selection,
+            self.selection_text,
+            self.title.0,
+            self.title.1,
+            self.title_text,
+            self.inactive_title.0,
+            self.inactive_title.1,
+            self.inactive_title_text,
+            self.font_size,
+            self.title_font_size,
+        )
+    }
+}
@@ -0,0 +1,479 @@
+//! Screensavers drawn onto a canvas a frame at a time, and the settings for
+//! when Copland starts one.
+
+use std::collections::VecDeque;
+use std::f64::consts::PI;
+
+use gloo::storage::{LocalStorage, Storage};
+use rand::seq::SliceRandom;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use web_sys::CanvasRenderingContext2d;
+
+use crate::backgrounds::prefers_reduced_motion;
+
+const SETTINGS_KEY: &str = "screensaver";
+const STARS: usize = 300;
+const FLAGS: usize = 24;
+/// Points per Mystify polygon and how many old copies of it trail behind.
+const CORNERS: usize = 4;
+const TRAIL: usize = 8;
+const PIPE_CELL: f64 = 28.0;
+/// Pipes drawn before the screen is wiped and they start over.
+const PIPES_PER_SCREEN: usize = 12;
+const PIPE_COLOURS: [&str; 6] = [
+    "#d02020", "#20a020", "#2040d0", "#d0b020", "#a020c0", "#20b0b0",
+];
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "kebab-case")]
+pub enum Saver {
+    Starfield,
+    Mystify,
+    Pipes,
+    FlyingWindows,
+    Marquee,
+}
+impl Saver {
+    pub const ALL: [Saver; 5] = [
+        Saver::Starfield,
+        Saver::Mystify,
+        Saver::Pipes,
+        Saver::FlyingWindows,
+        Saver::Marquee,
+    ];
+
+    pub fn id(&self) -> &'static str {
+        match self {
+            Saver::Starfield => "starfield",
+            Saver::Mystify => "mystify",
+            Saver::Pipes => "pipes",
+            Saver::FlyingWindows => "flying-windows",
+            Saver::Marquee => "marquee",
+        }
+    }
+
+    pub fn by_id(id: &str) -> Option<Saver> {
+        Saver::ALL.into_iter().find(|saver| saver.id() == id)
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Saver::Starfield => "Starfield Simulation",
+            Saver::Mystify => "Mystify Your Mind",
+            Saver::Pipes => "Pipes",
+            Saver::FlyingWindows => "Flying Windows",
+            Saver::Marquee => "Scrolling Marquee",
+        }
+    }
+}
+
+/// Bounds on `Settings::wait`, like Win98's spin box.
+pub const MIN_WAIT: u32 = 1;
+pub const MAX_WAIT: u32 = 60;
+
+/// The Screen Saver tab, remembered between visits.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct Settings {
+    /// `None` turns the screensaver off.
+    pub saver: Option<Saver>,
+    /// Idle minutes before it starts.
+    pub wait: u32,
+    /// What the marquee scrolls.
+    pub text: String,
+}
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            saver: Some(Saver::Starfield),
+            wait: 5,
+            text: "Roan, yes that is me!".to_string(),
+        }
+    }
+}
+impl Settings {
+    /// The saved settings, or the defaults without a screensaver for
+    /// visitors who'd rather things didn't move.
+    pub fn load() -> Settings {
+        LocalStorage::get(SETTINGS_KEY)
+            .map(|settings: Settings| Settings {
+                wait: settings.wait.clamp(MIN_WAIT, MAX_WAIT),
+                ..settings
+            })
+            .unwrap_or_else(|_| Settings {
+                saver: Some(Saver::Starfield).filter(|_| !prefers_reduced_motion()),
+                ..Settings::default()
+            })
+    }
+
+    pub fn save(&self) {
+        LocalStorage::set(SETTINGS_KEY, self).ok();
+    }
+}
+
+/// Something flying towards the viewer, in the unit cube.
+pub struct Particle {
+    x: f64,
+    y: f64,
+    /// 1 is far away, 0 is at the screen.
+    z: f64,
+    phase: f64,
+}
+impl Particle {
+    fn random(z: f64) -> Particle {
+        let mut rng = rand::thread_rng();
+        Particle {
+            x: rng.gen_range(-1.0..1.0),
+            y: rng.gen_range(-1.0..1.0),
+            z,
+            phase: rng.gen_range(0.0..2.0 * PI),
+        }
+    }
+
+    /// Moves it `speed` closer, replacing it with a new far away one once it
+    /// has flown past. Returns where it is on screen.
+    fn fly(&mut self, speed: f64, width: f64, height: f64) -> (f64, f64) {
+        self.z -= speed;
+        let (mut x, mut y) = self.project(width, height);
+        if self.z <= speed || x < 0.0 || x > width || y < 0.0 || y > height {
+            *self = Particle::random(1.0);
+            (x, y) = self.project(width, height);
+        }
+        (x, y)
+    }
+
+    fn project(&self, width: f64, height: f64) -> (f64, f64) {
+        let scale = width.max(height) / 2.0;
+        (
+            width / 2.0 + self.x / self.z * scale,
+            height / 2.0 + self.y / self.z * scale,
+        )
+    }
+}
+
+/// A point bouncing around the screen, in units of its size.
+#[derive(Clone, Copy)]
+struct Bouncer {
+    x: f64,
+    y: f64,
+    dx: f64,
+    dy: f64,
+}
+impl Bouncer {
+    fn random() -> Bouncer {
+        let mut rng = rand::thread_rng();
+        Bouncer {
+            x: rng.gen_range(0.0..1.0),
+            y: rng.gen_range(0.0..1.0),
+            dx: rng.gen_range(0.002..0.008) * if rng.gen() { 1.0 } else { -1.0 },
+            dy: rng.gen_range(0.002..0.008) * if rng.gen() { 1.0 } else { -1.0 },
+        }
+    }
+
+    fn step(&mut self) {
+        self.x += self.dx;
+        self.y += self.dy;
+        if !(0.0..=1.0).contains(&self.x) {
+            self.dx = -self.dx;
+            self.x = self.x.clamp(0.0, 1.0);
+        }
+        if !(0.0..=1.0).contains(&self.y) {
+            self.dy = -self.dy;
+            self.y = self.y.clamp(0.0, 1.0);
+        }
+    }
+}
+
+pub struct Polygon {
+    corners: [Bouncer; CORNERS],
+    /// Where the corners were on previous frames, newest first.
+    trail: VecDeque<[Bouncer; CORNERS]>,
+    hue: f64,
+}
+
+/// Where the pipe currently being laid has got to.
+pub struct Pipes {
+    width: f64,
+    height: f64,
+    columns: i32,
+    rows: i32,
+    occupied: Vec<bool>,
+    head: Option<(i32, i32)>,
+    direction: (i32, i32),
+    colour: &'static str,
+    laid: usize,
+}
+impl Pipes {
+    fn new(width: f64, height: f64) -> Pipes {
+        let columns = (width / PIPE_CELL).floor().max(1.0) as i32;
+        let rows = (height / PIPE_CELL).floor().max(1.0) as i32;
+        Pipes {
+            width,
+            height,
+            columns,
+            rows,
+            occupied: vec![false; (columns * rows) as usize],
+            head: None,
+            direction: (1, 0),
+            colour: PIPE_COLOURS[0],
+            laid: 0,
+        }
+    }
+
+    fn free(&self, (column, row): (i32, i32)) -> bool {
+        (0..self.columns).contains(&column)
+            && (0..self.rows).contains(&row)
+            && !self.occupied[(row * self.columns + column) as usize]
+    }
+
+    fn occupy(&mut self, (column, row): (i32, i32)) {
+        self.occupied[(row * self.columns + column) as usize] = true;
+    }
+
+    fn centre((column, row): (i32, i32)) -> (f64, f64) {
+        (
+            (column as f64 + 0.5) * PIPE_CELL,
+            (row as f64 + 0.5) * PIPE_CELL,
+        )
+    }
+
+    fn joint(context: &CanvasRenderingContext2d, colour: &str, cell: (i32, i32)) {
+        let (x, y) = Pipes::centre(cell);
+        context.set_fill_style_str(colour);
+        context.begin_path();
+        context.arc(x, y, PIPE_CELL * 0.3, 0.0, 2.0 * PI).ok();
+        context.fill();
+    }
+
+    /// Starts a new pipe in a random free cell, or returns false if the
+    /// screen is full.
+    fn start(&mut self, context: &CanvasRenderingContext2d) -> bool {
+        let mut rng = rand::thread_rng();
+        let free: Vec<(i32, i32)> = (0..self.rows)
+            .flat_map(|row| (0..self.columns).map(move |column| (column, row)))
+            .filter(|&cell| self.free(cell))
+            .collect();
+        let Some(&cell) = free.choose(&mut rng) else {
+            return false;
+        };
+
+        self.colour = PIPE_COLOURS.choose(&mut rng).copied().unwrap_or("#fff");
+        self.direction = *[(1, 0), (-1, 0), (0, 1), (0, -1)].choose(&mut rng).unwrap();
+        self.occupy(cell);
+        self.head = Some(cell);
+        self.laid += 1;
+        Pipes::joint(context, self.colour, cell);
+        true
+    }
+
+    fn step(&mut self, context: &CanvasRenderingContext2d) {
+        let Some(head) = self.head else {
+            if self.laid >= PIPES_PER_SCREEN || !self.start(context) {
+                self.clear(context);
+            }
+            return;
+        };
+
+        let mut rng = rand::thread_rng();
+        let (dx, dy) = self.direction;
+        let mut directions = vec![(dy, dx), (-dy, -dx)];
+        directions.shuffle(&mut rng);
+        // Mostly keep going straight.
+        if rng.gen_bool(0.75) {
+            directions.insert(0, (dx, dy));
+        } else {
+            directions.push((dx, dy));
+        }
+        let next = directions
+            .into_iter()
+            .map(|(dx, dy)| ((dx, dy), (head.0 + dx, head.1 + dy)))
+            .find(|&(_, cell)| self.free(cell));
+
+        let Some((direction, cell)) = next else {
+            self.head = None;
+            return;
+        };
+        if direction != self.direction {
+            Pipes::joint(context, self.colour, head);
+        }
+        let (from, to) = (Pipes::centre(head), Pipes::centre(cell));
+        context.set_line_cap("round");
+        context.set_stroke_style_str(self.colour);
+        context.set_line_width(PIPE_CELL * 0.4);
+        context.begin_path();
+        context.move_to(from.0, from.1);
+        context.line_to(to.0, to.1);
+        context.stroke();
+        // A highlight down the middle so it looks round.
+        context.set_stroke_style_str("rgba(255, 255, 255, 0.35)");
+        context.set_line_width(PIPE_CELL * 0.1);
+        context.stroke();
+
+        self.occupy(cell);
+        self.direction = direction;
+        self.head = Some(cell);
+    }
+
+    fn clear(&mut self, context: &CanvasRenderingContext2d) {
+        *self = Pipes::new(self.width, self.height);
+        context.set_fill_style_str("#000");
+        context.fill_rect(0.0, 0.0, self.width, self.height);
+    }
+}
+
+/// A running screensaver.
+pub enum Animation {
+    Starfield(Vec<Particle>),
+    Mystify(Vec<Polygon>),
+    /// Set up on the first frame, once the canvas size is known.
+    Pipes(Option<Pipes>),
+    FlyingWindows(Vec<Particle>),
+    Marquee {
+        text: String,
+        x: Option<f64>,
+        y: f64,
+        hue: f64,
+    },
+}
+impl Animation {
+    pub fn new(saver: Saver, text: &str) -> Animation {
+        let mut rng = rand::thread_rng();
+        match saver {
+            Saver::Starfield => Animation::Starfield(
+                (0..STARS)
+                    .map(|_| Particle::random(rng.gen_range(0.05..1.0)))
+                    .collect(),
+            ),
+            Saver::Mystify => Animation::Mystify(
+                (0..2)
+                    .map(|i| Polygon {
+                        corners: [(); CORNERS].map(|_| Bouncer::random()),
+                        trail: VecDeque::with_capacity(TRAIL),
+                        hue: i as f64 * 180.0,
+                    })
+                    .collect(),
+            ),
+            Saver::Pipes => Animation::Pipes(None),
+            Saver::FlyingWindows => Animation::FlyingWindows(
+                (0..FLAGS)
+                    .map(|_| Particle::random(rng.gen_range(0.1..1.0)))
+                    .collect(),
+            ),
+            Saver::Marquee => Animation::Marquee {
+                text: text.to_string(),
+                x: None,
+                y: rng.gen_range(0.2..0.8),
+                hue: rng.gen_range(0.0..360.0),
+            },
+        }
+    }
+
+    /// Draws the next frame onto a `width` by `height` canvas.
+    pub fn step(&mut self, context: &CanvasRenderingContext2d, width: f64, height: f64) {
+        match self {
+            Animation::Starfield(stars) => {
+                context.set_fill_style_str("#000");
+                context.fill_rect(0.0, 0.0, width, height);
+                context.set_fill_style_str("#fff");
+                for star in stars {
+                    let (x, y) = star.fly(0.008, width, height);
+                    let size = (1.0 - star.z) * 3.0 + 0.5;
+                    context.fill_rect(x, y, size, size);
+                }
+            }
+            Animation::Mystify(polygons) => {
+                context.set_fill_style_str("#000");
+                context.fill_rect(0.0, 0.0, width, height);
+                context.set_line_width(1.5);
+                for polygon in polygons {
+                    polygon.corners.iter_mut().for_each(Bouncer::step);
+                    polygon.trail.push_front(polygon.corners);
+                    polygon.trail.truncate(TRAIL);
+                    polygon.hue = (polygon.hue + 0.5) % 360.0;
+
+                    let colour = format!("hsl({}, 100%, 60%)", polygon.hue);
+                    context.set_stroke_style_str(&colour);
+                    for corners in &polygon.trail {
+                        context.begin_path();
+                        for (i, corner) in corners.iter().enumerate() {
+                            let (x, y) = (corner.x * width, corner.y * height);
+                            if i == 0 {
+                                context.move_to(x, y);
+                            } else {
+                                context.line_to(x, y);
+                            }
+                        }
+                        context.close_path();
+                        context.stroke();
+                    }
+                }
+            }
+            Animation::Pipes(pipes) => {
+                let sized = matches!(pipes, Some(p) if p.width == width && p.height == height);
+                if !sized {
+                    pipes.insert(Pipes::new(width, height)).clear(context);
+                }
+                if let Some(pipes) = pipes {
+                    pipes.step(context);
+                }
+            }
+            Animation::FlyingWindows(flags) => {
+                context.set_fill_style_str("#000");
+                context.fill_rect(0.0, 0.0, width, height);
+                // Furthest first, so nearer flags are drawn over them.
+                flags.sort_by(|a, b| b.z.total_cmp(&a.z));
+                for flag in flags {
+                    let (x, y) = flag.fly(0.004, width, height);
+                    flag.phase += 0.15;
+                    let size = (1.0 - flag.z) * width.min(height) / 8.0 + 2.0;
+                    draw_flag(context, x, y, size, flag.phase);
+                }
+            }
+            Animation::Marquee { text, x, y, hue } => {
+                context.set_fill_style_str("#000");
+                context.fill_rect(0.0, 0.0, width, height);
+                let font_size = (height / 6.0).max(12.0);
+                context.set_font(&format!(
+                    "{}px \"Pixelated MS Sans Serif\", Arial",
+                    font_size
+                ));
+                context.set_text_baseline("middle");
+                let text_width = context
+                    .measure_text(text)
+                    .map(|metrics| metrics.width())
+                    .unwrap_or_default();
+
+                let left = x.get_or_insert(width);
+                *left -= (width / 300.0).max(1.0);
+                if *left + text_width < 0.0 {
+                    let mut rng = rand::thread_rng();
+                    *left = width;
+                    *y = rng.gen_range(0.2..0.8);
+                    *hue = rng.gen_range(0.0..360.0);
+                }
+                let colour = format!("hsl({}, 100%, 60%)", hue);
+                context.set_fill_style_str(&colour);
+                context.fill_text(text, *left, *y * height).ok();
+            }
+        }
+    }
+}
+
+/// The four-colour flag, centred on `x`, `y` and waving with `phase`.
+fn draw_flag(context: &CanvasRenderingContext2d, x: f64, y: f64, size: f64, phase: f64) {
+    let half = size / 2.0;
+    let gap = size / 16.0;
+    let panes = [
+        ("#f03c24", -1.0, -1.0),
+        ("#7fba00", 1.0, -1.0),
+        ("#00a4ef", -1.0, 1.0),
+        ("#ffb900", 1.0, 1.0),
+    ];
+    for (colour, column, row) in panes {
+        let wave = (phase + column).sin() * size / 12.0;
+        let left = x + if column < 0.0 { -half } else { gap };
+        let top = y + wave + if row < 0.0 { -half } else { gap };
+        context.set_fill_style_str(colour);
+        context.fill_rect(left, top, half - gap, half - gap);
+    }
+}
@@ -0,0 +1,148 @@
+use gloo::timers::callback::Interval;
+use js_sys::{Array, Date, Intl, Object, Reflect};
+use wasm_bindgen::JsValue;
+use yew::{function_component, html, use_effect_with_deps, use_state, Properties};
+
+const MS_PER_SECOND: f64 = 1_000.0;
+const MS_PER_MINUTE: f64 = 60_000.0;
+const MS_PER_HOUR: f64 = 3_600_000.0;
+const MS_PER_DAY: f64 = 86_400_000.0;
+
+/// Builds an `Intl` options object from string key/value pairs.
+fn options(pairs: &[(&str, &str)]) -> Object {
+    let options = Object::new();
+    for (key, value) in pairs {
+        Reflect::set(&options, &JsValue::from(*key), &JsValue::from(*value)).ok();
+    }
+    options
+}
+
+/// Formats a date with `Intl.DateTimeFormat` in the visitor's locale and timezone.
+fn format_with(date: &Date, pairs: &[(&str, &str)]) -> String {
+    // An empty locale list makes Intl fall back to the browser's own locale.
+    let formatter = Intl::DateTimeFormat::new(&Array::new(), &options(pairs));
+    formatter
+        .format()
+        .call1(&JsValue::UNDEFINED, date)
+        .ok()
+        .and_then(|s| s.as_string())
+        .unwrap_or_default()
+}
+
+/// Converts unix seconds (as used by our APIs) into a JS `Date`.
+pub fn date_from_unix(seconds: u64) -> Date {
+    Date::new(&JsValue::from(seconds as f64 * MS_PER_SECOND))
+}
+
+/// Parses an ISO 8601 date string into a JS `Date`.
+pub fn date_from_iso(iso: &str) -> Date {
+    Date::new(&JsValue::from(iso))
+}
+
+/// Hours and minutes, e.g. for the taskbar clock.
+pub fn format_clock(date: &Date) -> String {
+    format_with(date, &[("hour", "2-digit"), ("minute", "2-digit")])
+}
+
+/// A short calendar date such as "19 Oct 2026".
+pub fn format_date(date: &Date) -> String {
+    format_with(date, &[("dateStyle", "medium")])
+}
+
+/// A full date with time, e.g. for sticky note timestamps and tooltips.
+pub fn format_date_time(date: &Date) -> String {
+    format_with(date, &[("dateStyle", "medium"), ("timeStyle", "short")])
+}
+
+/// Whole calendar months between two dates, respecting real month lengths.
+fn months_between(then: &Date, now: &Date) -> i32 {
+    let years = now.get_full_year() as i32 - then.get_full_year() as i32;
+    let months = years * 12 + now.get_month() as i32 - then.get_month() as i32;
+    if now.get_date() < then.get_date() {
+        months - 1
+    } else {
+        months
+    }
+}
+
+/// Picks the largest sensible unit for the gap between `then` and `now`.
+/// Returns a negative value for past dates as `Intl.RelativeTimeFormat` expects.
+fn relative_unit(then: &Date, now: &Date) -> (f64, &'static str) {
+    let elapsed = now.get_time() - then.get_time();
+    let sign = if elapsed < 0.0 { 1.0 } else { -1.0 };
+    let elapsed = elapsed.abs();
+
+    if elapsed < MS_PER_MINUTE {
+        return (sign * (elapsed / MS_PER_SECOND).floor(), "second");
+    }
+    if elapsed < MS_PER_HOUR {
+        return (sign * (elapsed / MS_PER_MINUTE).floor(), "minute");
+    }
+    if elapsed < MS_PER_DAY {
+        return (sign * (elapsed / MS_PER_HOUR).floor(), "hour");
+    }
+
+    let months = months_between(then, now).abs();
+    if months == 0 {
+        let days = (elapsed / MS_PER_DAY).floor();
+        if days < 7.0 {
+            (sign * days, "day")
+        } else {
+            (sign * (days / 7.0).floor(), "week")
+        }
+    } else if months < 12 {
+        (sign * months as f64, "month")
+    } else {
+        (sign * (months / 12) as f64, "year")
+    }
+}
+
+/// "3 minutes ago", "yesterday", "last year" etc. in the visitor's locale.
+pub fn format_relative(then: &Date, now: &Date) -> String {
+    let (value, unit) = relative_unit(then, now);
+    let formatter =
+        Intl::RelativeTimeFormat::new(&Array::new(), &options(&[("numeric", "auto")]));
+    formatter.format(value, unit).into()
+}
+
+/// How often a relative label needs re-rendering to stay accurate.
+fn refresh_period(elapsed_ms: f64) -> u32 {
+    if elapsed_ms < MS_PER_MINUTE {
+        1_000
+    } else {
+        60_000
+    }
+}
+
+#[derive(Properties, PartialEq, Eq)]
+pub struct RelativeTimeProps {
+    /// Unix timestamp in seconds.
+    pub timestamp: u64,
+}
+
+/// A self-refreshing relative time label with the absolute time as a tooltip.
+#[function_component(RelativeTime)]
+pub fn relative_time(props: &RelativeTimeProps) -> Html {
+    let now = use_state(Date::now);
+    let then = date_from_unix(props.timestamp);
+    let period = refresh_period(*now - then.get_time());
+
+    {
+        let now = now.clone();
+        use_effect_with_deps(
+            move |_| {
+                let interval = Interval::new(period, move || now.set(Date::now()));
+                move || drop(interval)
+            },
+            (props.timestamp, period),
+        );
+    }
+
+    let datetime: String = then.to_iso_string().into();
+
+    html! {
+        <time {datetime} title={format_date_time(&then)}>
+            { format_relative(&then, &Date::new(&JsValue::from(*now))) }
+        </time>
+    }
+}
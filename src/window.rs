@@ -3,9 +3,11 @@ use std::fmt::Write as _;
 use yew::html::Scope;
 use yew::{classes, html, Html};
 
+use crate::colour::Accent;
 use crate::copland::{Copland, CoplandMsg, MoveEvent};
 use crate::windows::{
-    AboutMe, BackgroundSelector, Films, Home, PhotoViewer, Projects, Socials, Spotify, StickyNote,
+    AboutMe, DisplayProperties, Film, FilmDetails, Films, Home, Photo, PhotoProperties,
+    PhotoViewer, ProjectData, ProjectReadme, Projects, Socials, Spotify, Status, StickyNote,
 };
 
 #[derive(Debug, PartialEq, Eq, Clone)]
@@ -29,11 +31,15 @@ pub enum WindowId {
     Spotify,
     AboutMe,
     SocialLinks,
-    BackgroundSelector,
+    DisplayProperties,
     Projects,
     Films,
     PhotoViewer,
+    Status,
     StickyNote(usize),
+    FilmDetails(usize),
+    PhotoProperties(usize),
+    ProjectReadme(usize),
 }
 impl fmt::Display for WindowId {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -42,11 +48,15 @@ impl fmt::Display for WindowId {
             Self::Spotify => "Spotify".to_string(),
             Self::AboutMe => "AboutMe".to_string(),
             Self::SocialLinks => "SocialLinks".to_string(),
-            Self::BackgroundSelector => "BackgroundSelector".to_string(),
+            Self::DisplayProperties => "DisplayProperties".to_string(),
             Self::Projects => "Projects".to_string(),
             Self::Films => "Letterboxd".to_string(),
             Self::PhotoViewer => "Photo Viewer".to_string(),
+            Self::Status => "Status".to_string(),
             Self::StickyNote(index) => format!("StickyNote({})", index),
+            Self::FilmDetails(id) => format!("FilmDetails({})", id),
+            Self::PhotoProperties(id) => format!("PhotoProperties({})", id),
+            Self::ProjectReadme(id) => format!("ProjectReadme({})", id),
         };
         write!(f, "{}", id)
     }
@@ -71,6 +81,7 @@ pub struct Window {
     pub height: Option<u32>,
     pub icon: String,
     pub title: String,
+    pub accent: Option<Accent>,
     pub body: Html,
 }
 impl Window {
@@ -79,12 +90,20 @@ impl Window {
         let open_spotify =
             link.callback(move |_| CoplandMsg::OpenWindow(Self::spotify(&spotify_link)));
         let open_about_me = link.callback(|_| CoplandMsg::OpenWindow(Self::about_me()));
-        let open_background =
-            link.callback(|_| CoplandMsg::OpenWindow(Self::background_selector()));
+        let display_link = link.clone();
+        let open_background = link.callback(move |_| {
+            CoplandMsg::OpenWindow(Self::display_properties(&display_link))
+        });
         let open_socials = link.callback(|_| CoplandMsg::OpenWindow(Self::socials()));
-        let open_projects = link.callback(|_| CoplandMsg::OpenWindow(Self::projects()));
-        let open_films = link.callback(|_| CoplandMsg::OpenWindow(Self::films()));
-        let open_photo_viewer = link.callback(|_| CoplandMsg::OpenWindow(Self::photo_viewer()));
+        let projects_link = link.clone();
+        let open_projects =
+            link.callback(move |_| CoplandMsg::OpenWindow(Self::projects(&projects_link)));
+        let films_link = link.clone();
+        let open_films = link.callback(move |_| CoplandMsg::OpenWindow(Self::films(&films_link)));
+        let photo_viewer_link = link.clone();
+        let open_photo_viewer =
+            link.callback(move |_| CoplandMsg::OpenWindow(Self::photo_viewer(&photo_viewer_link)));
+        let open_status = link.callback(|_| CoplandMsg::OpenWindow(Self::status()));
 
         Window {
             id: WindowId::Home,
@@ -97,8 +116,9 @@ impl Window {
             height: None,
             icon: "assets/icons/computer_explorer-5.png".to_string(),
             title: "Home".to_string(),
+            accent: None,
             body: html! {
-                <Home {open_background} {open_spotify} {open_about_me} {open_socials} {open_projects} {open_films} {open_photo_viewer}></Home>
+                <Home {open_background} {open_spotify} {open_about_me} {open_socials} {open_projects} {open_films} {open_photo_viewer} {open_status}></Home>
             },
         }
     }
@@ -115,6 +135,7 @@ impl Window {
             height: None,
             icon: "assets/icons/msg_information-0.png".to_string(),
             title: "About Me".to_string(),
+            accent: None,
             body: html! {
                 <AboutMe></AboutMe>
             },
@@ -124,6 +145,9 @@ impl Window {
     pub fn spotify(link: &Scope<Copland>) -> Self {
         let resize_window =
             link.callback(|new_height| CoplandMsg::ResizeWindow(WindowId::Spotify, new_height));
+        let set_accent =
+            link.callback(|accent| CoplandMsg::SetWindowAccent(WindowId::Spotify, accent));
+        let notify = link.callback(CoplandMsg::Notify);
 
         Window {
             id: WindowId::Spotify,
@@ -136,26 +160,33 @@ impl Window {
             height: None,
             icon: "assets/icons/spotify.svg".to_string(),
             title: "Spotify".to_string(),
+            accent: None,
             body: html! {
-                <Spotify {resize_window}></Spotify>
+                <Spotify {resize_window} {set_accent} {notify}></Spotify>
             },
         }
     }
 
-    pub fn background_selector() -> Self {
+    pub fn display_properties(link: &Scope<Copland>) -> Self {
+        let on_close =
+            link.callback(|_| CoplandMsg::CloseWindow(WindowId::DisplayProperties));
+        let on_preview =
+            link.callback(|(saver, text)| CoplandMsg::StartScreenSaver(saver, text));
+
         Window {
-            id: WindowId::BackgroundSelector,
+            id: WindowId::DisplayProperties,
             state: WindowState::Open,
             close: WindowClose::Close,
             z_index: 0,
             top: WindowPosition::Close(0),
             left: WindowPosition::Close(0),
-            width: 300,
+            width: 360,
             height: None,
-            icon: "assets/icons/kodak_imaging-0.png".to_string(),
-            title: "Select Background".to_string(),
+            icon: "assets/icons/display_properties-0.png".to_string(),
+            title: "Display Properties".to_string(),
+            accent: None,
             body: html! {
-                <BackgroundSelector></BackgroundSelector>
+                <DisplayProperties {on_close} {on_preview}></DisplayProperties>
             },
         }
     }
@@ -172,13 +203,17 @@ impl Window {
             height: None,
             icon: "assets/icons/netmeeting-0.png".to_string(),
             title: "Social links ツ".to_string(),
+            accent: None,
             body: html! {
                 <Socials></Socials>
             },
         }
     }
 
-    pub fn projects() -> Self {
+    pub fn projects(link: &Scope<Copland>) -> Self {
+        let open_readme =
+            link.callback(|project| CoplandMsg::OpenWindow(Self::project_readme(project)));
+
         Window {
             id: WindowId::Projects,
             state: WindowState::Open,
@@ -186,17 +221,39 @@ impl Window {
             z_index: 0,
             top: WindowPosition::Half,
             left: WindowPosition::Half,
-            width: 350,
+            width: 480,
             height: None,
             icon: "assets/icons/keyboard-5.png".to_string(),
             title: "(Some) of my projects".to_string(),
+            accent: None,
             body: html! {
-                <Projects></Projects>
+                <Projects {open_readme}></Projects>
             },
         }
     }
 
-    pub fn films() -> Self {
+    pub fn project_readme(project: ProjectData) -> Self {
+        Window {
+            id: WindowId::ProjectReadme(project.id()),
+            state: WindowState::Open,
+            close: WindowClose::Close,
+            z_index: 0,
+            top: WindowPosition::Half,
+            left: WindowPosition::Half,
+            width: 560,
+            height: Some(450),
+            icon: "assets/icons/write_wordpad-0.png".to_string(),
+            title: format!("{} README - WordPad", project.title),
+            accent: None,
+            body: html! {
+                <ProjectReadme {project}></ProjectReadme>
+            },
+        }
+    }
+
+    pub fn films(link: &Scope<Copland>) -> Self {
+        let open_film = link.callback(|film| CoplandMsg::OpenWindow(Self::film_details(film)));
+
         Window {
             id: WindowId::Films,
             state: WindowState::Open,
@@ -208,8 +265,28 @@ impl Window {
             height: Some(400),
             icon: "assets/icons/camera3_vid-2.png".to_string(),
             title: "Letterboxd".to_string(),
+            accent: None,
+            body: html! {
+                <Films {open_film}></Films>
+            },
+        }
+    }
+
+    pub fn film_details(film: Film) -> Self {
+        Window {
+            id: WindowId::FilmDetails(film.id()),
+            state: WindowState::Open,
+            close: WindowClose::Close,
+            z_index: 0,
+            top: WindowPosition::Half,
+            left: WindowPosition::Half,
+            width: 450,
+            height: None,
+            icon: "assets/icons/camera3_vid-2.png".to_string(),
+            title: film.name.clone(),
+            accent: None,
             body: html! {
-                <Films></Films>
+                <FilmDetails {film}></FilmDetails>
             },
         }
     }
@@ -226,13 +303,17 @@ impl Window {
             height: None,
             icon: "assets/icons/template_empty-5.png".to_string(),
             title: format!("sticky note {id}"),
+            accent: None,
             body: html! {
                 <StickyNote {id} {content} {created_at}></StickyNote>
             },
         }
     }
 
-    pub fn photo_viewer() -> Self {
+    pub fn photo_viewer(link: &Scope<Copland>) -> Self {
+        let open_properties =
+            link.callback(|photo| CoplandMsg::OpenWindow(Self::photo_properties(photo)));
+
         Window {
             id: WindowId::PhotoViewer,
             state: WindowState::Open,
@@ -240,12 +321,51 @@ impl Window {
             z_index: 0,
             left: WindowPosition::Half,
             top: WindowPosition::Half,
-            width: 500,
+            width: 700,
             height: None,
             icon: "assets/icons/kodak_imaging-0.png".to_string(),
             title: "Photo Viewer".to_string(),
+            accent: None,
             body: html! {
-                <PhotoViewer></PhotoViewer>
+                <PhotoViewer {open_properties}></PhotoViewer>
+            },
+        }
+    }
+
+    pub fn photo_properties(photo: Photo) -> Self {
+        Window {
+            id: WindowId::PhotoProperties(photo.id()),
+            state: WindowState::Open,
+            close: WindowClose::Close,
+            z_index: 0,
+            left: WindowPosition::Half,
+            top: WindowPosition::Half,
+            width: 320,
+            height: None,
+            icon: "assets/icons/kodak_imaging-0.png".to_string(),
+            title: format!("{} Properties", photo.title),
+            accent: None,
+            body: html! {
+                <PhotoProperties {photo}></PhotoProperties>
+            },
+        }
+    }
+
+    pub fn status() -> Self {
+        Window {
+            id: WindowId::Status,
+            state: WindowState::Open,
+            close: WindowClose::Close,
+            z_index: 0,
+            top: WindowPosition::Close(0),
+            left: WindowPosition::Half,
+            width: 300,
+            height: None,
+            icon: "assets/icons/discord.svg".to_string(),
+            title: "Status".to_string(),
+            accent: None,
+            body: html! {
+                <Status></Status>
             },
         }
     }
@@ -286,6 +406,9 @@ impl Window {
             }
         };
         write!(style, "z-index: {};", self.z_index).ok();
+        if let Some(accent) = self.accent {
+            style.push_str(&accent.css_variables());
+        }
 
         let mut focused_class = vec!["title-bar"];
 
@@ -293,10 +416,13 @@ impl Window {
             focused_class.push("inactive");
         }
 
-        let window_class = match self.id {
+        let mut window_class = match self.id {
             WindowId::StickyNote(_) => vec!["window", "sticky-note"],
             _ => vec!["window"],
         };
+        if self.accent.is_some() {
+            window_class.push("accented");
+        }
 
         html! {
             <div
@@ -0,0 +1,437 @@
+use web_sys::{Element, HtmlInputElement, HtmlSelectElement, KeyboardEvent};
+use yew::{
+    classes, function_component, html, use_context, use_effect_with_deps, use_node_ref, use_state,
+    Callback, Children, Event, Html, Properties, TargetCast,
+};
+
+use crate::backgrounds::{prefers_reduced_motion, saving_data, Background};
+use crate::copland::{ThemeAction, ThemeContext};
+use crate::schemes::{Scheme, SCHEMES};
+use crate::screensavers::{Saver, Settings as ScreenSaverSettings, MAX_WAIT, MIN_WAIT};
+use crate::windows::ScreenSaver;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum DisplayTab {
+    Background,
+    ScreenSaver,
+    Appearance,
+}
+
+#[derive(Properties, PartialEq)]
+struct MonitorProps {
+    #[prop_or_default]
+    children: Children,
+}
+
+/// The little CRT that previews what the desktop will look like.
+#[function_component(Monitor)]
+fn monitor(props: &MonitorProps) -> Html {
+    html! {
+        <div class="display-monitor" aria-hidden="true">
+            <div class="display-monitor-screen">
+                { for props.children.iter() }
+            </div>
+            <div class="display-monitor-stand"></div>
+        </div>
+    }
+}
+
+/// The animated GIF if there is one, otherwise the video itself, or just the
+/// poster when the desktop isn't animating either.
+fn preview(background: &Background, animate: bool) -> Html {
+    let style = background
+        .colour
+        .as_ref()
+        .map(|colour| format!("background-color: {};", colour));
+    if !animate {
+        match background.poster_url() {
+            Some(src) => html! {
+                <img key={background.id} {src} alt="" {style} />
+            },
+            None => html! {
+                <div key={background.id} class="display-monitor-colour" {style}></div>
+            },
+        }
+    } else if background.formats.iter().any(|f| f == "gif") {
+        html! {
+            <img key={background.id} src={background.url("gif")} alt="" {style} />
+        }
+    } else {
+        html! {
+            <video key={background.id} {style} autoplay=true muted=true loop=true playsinline=true>
+                {
+                    background.videos().map(|(src, mime)| html! {
+                        <source {src} type={mime}/>
+                    }).collect::<Html>()
+                }
+            </video>
+        }
+    }
+}
+
+/// A list thumbnail: the still poster, animating once it's selected.
+fn thumbnail(background: &Background, animate: bool) -> Html {
+    let has_gif = background.formats.iter().any(|f| f == "gif");
+    let src = if animate && has_gif {
+        Some(background.url("gif"))
+    } else {
+        background.poster_url()
+    };
+    let style = background
+        .colour
+        .as_ref()
+        .map(|colour| format!("background-color: {};", colour));
+
+    match src {
+        Some(src) => html! {
+            <img class="display-thumb" {src} alt="" loading="lazy" {style} />
+        },
+        None => html! {
+            <div class="display-thumb" {style}></div>
+        },
+    }
+}
+
+/// A couple of windows drawn in `scheme`, like the sample on Win98's
+/// Appearance tab.
+fn scheme_sample(scheme: &Scheme) -> Html {
+    html! {
+        <div class="display-scheme-sample" style={scheme.css_variables()} aria-hidden="true">
+            <div class="window">
+                <div class="title-bar inactive">
+                    <div class="title-bar-text">{ "Inactive Window" }</div>
+                </div>
+            </div>
+            <div class="window">
+                <div class="title-bar">
+                    <div class="title-bar-text">{ "Active Window" }</div>
+                </div>
+                <div class="window-body">
+                    <p>{ "Window Text" }</p>
+                    <ul class="tree-view">
+                        <li>{ "Normal" }</li>
+                        <li><a href="#" tabindex="-1">{ "Selected" }</a></li>
+                    </ul>
+                    <button tabindex="-1">{ "OK" }</button>
+                </div>
+            </div>
+        </div>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct DisplayPropertiesProps {
+    pub on_close: Callback<()>,
+    /// Runs a screensaver full screen, with the marquee's text.
+    pub on_preview: Callback<(Saver, String)>,
+}
+
+/// Win98's Display Properties: pick a background or colour scheme and preview
+/// it, only changing the desktop on Apply or OK.
+#[function_component(DisplayProperties)]
+pub fn display_properties(props: &DisplayPropertiesProps) -> Html {
+    let theme = use_context::<ThemeContext>().unwrap();
+    let tab = use_state(|| DisplayTab::Background);
+    let pending = {
+        let background = theme.background;
+        use_state(move || background)
+    };
+    let pending_random = {
+        let random = theme.random;
+        use_state(move || random)
+    };
+    let pending_scheme = {
+        let scheme = theme.scheme;
+        use_state(move || scheme)
+    };
+    let pending_screensaver = {
+        let screensaver = theme.screensaver.clone();
+        use_state(move || screensaver)
+    };
+    let show_saver_settings = use_state(|| false);
+    let list = use_node_ref();
+    let animate = !theme.paused && !prefers_reduced_motion() && !saving_data();
+
+    // Keep the chosen background in view as the arrow keys move it.
+    {
+        let list = list.clone();
+        use_effect_with_deps(
+            move |pending| {
+                let item = list
+                    .cast::<Element>()
+                    .and_then(|l| l.children().item(*pending as u32));
+                if let Some(item) = item {
+                    item.scroll_into_view_with_bool(false);
+                }
+                || ()
+            },
+            *pending,
+        );
+    }
+
+    let changed = *pending != theme.background
+        || *pending_random != theme.random
+        || *pending_scheme != theme.scheme
+        || *pending_screensaver != theme.screensaver;
+    let apply = {
+        let theme = theme.clone();
+        let pending = *pending;
+        let pending_random = *pending_random;
+        let pending_scheme = *pending_scheme;
+        let pending_screensaver = (*pending_screensaver).clone();
+        move || {
+            if pending_screensaver != theme.screensaver {
+                theme.dispatch(ThemeAction::SetScreenSaver(pending_screensaver.clone()));
+            }
+            if pending_scheme != theme.scheme {
+                theme.dispatch(ThemeAction::SetScheme(pending_scheme));
+            }
+            if pending != theme.background {
+                theme.dispatch(ThemeAction::Select(pending));
+            }
+            if pending_random != theme.random {
+                theme.dispatch(ThemeAction::SetRandom(pending_random));
+            }
+        }
+    };
+    let on_apply = {
+        let apply = apply.clone();
+        Callback::from(move |_| apply())
+    };
+    let on_ok = {
+        let on_close = props.on_close.clone();
+        Callback::from(move |_| {
+            apply();
+            on_close.emit(());
+        })
+    };
+    let on_cancel = props.on_close.reform(|_| ());
+
+    let on_random = {
+        let pending_random = pending_random.clone();
+        Callback::from(move |e: Event| {
+            pending_random.set(e.target_unchecked_into::<HtmlInputElement>().checked());
+        })
+    };
+    let on_scheme = {
+        let pending_scheme = pending_scheme.clone();
+        Callback::from(move |e: Event| {
+            let id = e.target_unchecked_into::<HtmlSelectElement>().value();
+            if let Some(scheme) = Scheme::by_id(&id) {
+                pending_scheme.set(scheme);
+            }
+        })
+    };
+    // Every screensaver field edits a copy of the pending settings.
+    let edit_screensaver = |edit: fn(&mut ScreenSaverSettings, String)| {
+        let pending_screensaver = pending_screensaver.clone();
+        move |value: String| {
+            let mut settings = (*pending_screensaver).clone();
+            edit(&mut settings, value);
+            pending_screensaver.set(settings);
+        }
+    };
+    let on_saver = {
+        let edit = edit_screensaver(|settings, id| settings.saver = Saver::by_id(&id));
+        Callback::from(move |e: Event| edit(e.target_unchecked_into::<HtmlSelectElement>().value()))
+    };
+    let on_wait = {
+        let edit = edit_screensaver(|settings, wait| {
+            if let Ok(wait) = wait.parse::<u32>() {
+                settings.wait = wait.clamp(MIN_WAIT, MAX_WAIT);
+            }
+        });
+        Callback::from(move |e: Event| edit(e.target_unchecked_into::<HtmlInputElement>().value()))
+    };
+    let on_marquee_text = {
+        let edit = edit_screensaver(|settings, text| settings.text = text);
+        Callback::from(move |e: Event| edit(e.target_unchecked_into::<HtmlInputElement>().value()))
+    };
+    let on_saver_settings = {
+        let show_saver_settings = show_saver_settings.clone();
+        Callback::from(move |_| show_saver_settings.set(!*show_saver_settings))
+    };
+    let on_saver_preview = {
+        let on_preview = props.on_preview.clone();
+        let settings = (*pending_screensaver).clone();
+        Callback::from(move |_| {
+            if let Some(saver) = settings.saver {
+                on_preview.emit((saver, settings.text.clone()));
+            }
+        })
+    };
+    let onkeydown = {
+        let pending = pending.clone();
+        let last = theme.backgrounds.len().saturating_sub(1);
+        Callback::from(move |e: KeyboardEvent| {
+            let next = match e.key().as_str() {
+                "ArrowDown" => (*pending + 1).min(last),
+                "ArrowUp" => pending.saturating_sub(1),
+                "Home" => 0,
+                "End" => last,
+                _ => return,
+            };
+            e.prevent_default();
+            pending.set(next);
+        })
+    };
+
+    let tab_button = |target: DisplayTab, label: &str| {
+        let tab = tab.clone();
+        let selected = *tab == target;
+        html! {
+            <button
+                role="tab"
+                aria-selected={selected.to_string()}
+                onclick={Callback::from(move |_| tab.set(target))}
+            >
+                { label }
+            </button>
+        }
+    };
+
+    let body = match *tab {
+        DisplayTab::Background => html! {
+            <>
+                <Monitor>
+                    { theme.backgrounds.get(*pending).map(|b| preview(b, animate)).unwrap_or_default() }
+                </Monitor>
+                <label id="display-backgrounds-label">{ "Wallpaper" }</label>
+                <ul
+                    class="display-backgrounds"
+                    role="listbox"
+                    aria-labelledby="display-backgrounds-label"
+                    tabindex="0"
+                    ref={list}
+                    {onkeydown}
+                >
+                    {
+                        theme.backgrounds.iter().enumerate().map(|(i, background)| {
+                            let selected = i == *pending;
+                            let pending = pending.clone();
+                            html! {
+                                <li
+                                    role="option"
+                                    aria-selected={selected.to_string()}
+                                    class={classes!(selected.then_some("selected"))}
+                                    onclick={Callback::from(move |_| pending.set(i))}
+                                >
+                                    { thumbnail(background, selected && animate) }
+                                    <span>{ background.name.clone() }</span>
+                                </li>
+                            }
+                        }).collect::<Html>()
+                    }
+                </ul>
+                <div class="field-row">
+                    <input type="checkbox" id="background-random" checked={*pending_random} onchange={on_random} />
+                    <label for="background-random">{ "Pick a random wallpaper on each visit" }</label>
+                </div>
+            </>
+        },
+        DisplayTab::ScreenSaver => {
+            let settings = &*pending_screensaver;
+            let marquee = settings.saver == Some(Saver::Marquee);
+            html! {
+                <>
+                    <Monitor>
+                        if let Some(saver) = settings.saver {
+                            <ScreenSaver {saver} text={settings.text.clone()} />
+                        } else {
+                            <div class="display-monitor-off"></div>
+                        }
+                    </Monitor>
+                    <fieldset>
+                        <legend>{ "Screen Saver" }</legend>
+                        <div class="field-row">
+                            <select aria-label="Screen saver" onchange={on_saver}>
+                                <option value="" selected={settings.saver.is_none()}>{ "(None)" }</option>
+                                {
+                                    Saver::ALL.into_iter().map(|saver| html! {
+                                        <option value={saver.id()} selected={settings.saver == Some(saver)}>
+                                            { saver.name() }
+                                        </option>
+                                    }).collect::<Html>()
+                                }
+                            </select>
+                            <button
+                                disabled={!marquee}
+                                aria-expanded={(marquee && *show_saver_settings).to_string()}
+                                onclick={on_saver_settings}
+                            >
+                                { "Settings..." }
+                            </button>
+                            <button disabled={settings.saver.is_none()} onclick={on_saver_preview}>
+                                { "Preview" }
+                            </button>
+                        </div>
+                        if marquee && *show_saver_settings {
+                            <div class="field-row-stacked">
+                                <label for="screensaver-text">{ "Text:" }</label>
+                                <input
+                                    id="screensaver-text"
+                                    type="text"
+                                    value={settings.text.clone()}
+                                    onchange={on_marquee_text}
+                                />
+                            </div>
+                        }
+                        <div class="field-row">
+                            <label for="screensaver-wait">{ "Wait:" }</label>
+                            <input
+                                id="screensaver-wait"
+                                type="number"
+                                min={MIN_WAIT.to_string()}
+                                max={MAX_WAIT.to_string()}
+                                value={settings.wait.to_string()}
+                                disabled={settings.saver.is_none()}
+                                onchange={on_wait}
+                            />
+                            <label for="screensaver-wait">{ "minutes" }</label>
+                        </div>
+                    </fieldset>
+                </>
+            }
+        }
+        DisplayTab::Appearance => html! {
+            <>
+                { scheme_sample(*pending_scheme) }
+                <div class="field-row">
+                    <label for="display-scheme">{ "Scheme:" }</label>
+                    <select id="display-scheme" onchange={on_scheme}>
+                        {
+                            SCHEMES.iter().map(|scheme| html! {
+                                <option
+                                    value={scheme.id}
+                                    selected={scheme == *pending_scheme}
+                                >
+                                    { scheme.name }
+                                </option>
+                            }).collect::<Html>()
+                        }
+                    </select>
+                </div>
+            </>
+        },
+    };
+
+    html! {
+        <div class="display-properties">
+            <menu role="tablist" class="tabs">
+                { tab_button(DisplayTab::Background, "Background") }
+                { tab_button(DisplayTab::ScreenSaver, "Screen Saver") }
+                { tab_button(DisplayTab::Appearance, "Appearance") }
+            </menu>
+            <div class="window" role="tabpanel">
+                <div class="window-body">
+                    { body }
+                </div>
+            </div>
+            <div class="display-properties-buttons">
+                <button onclick={on_ok}>{ "OK" }</button>
+                <button onclick={on_cancel}>{ "Cancel" }</button>
+                <button onclick={on_apply} disabled={!changed}>{ "Apply" }</button>
+            </div>
+        </div>
+    }
+}
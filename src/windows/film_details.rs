@@ -0,0 +1,68 @@
+use yew::{function_component, html, Html, Properties};
+
+use super::films::{format_stars, Film};
+use crate::time_format;
+
+fn format_runtime(minutes: u32) -> String {
+    if minutes >= 60 {
+        format!("{}h {}m", minutes / 60, minutes % 60)
+    } else {
+        format!("{}m", minutes)
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct FilmDetailsProps {
+    pub film: Film,
+}
+
+#[function_component(FilmDetails)]
+pub fn film_details(props: &FilmDetailsProps) -> Html {
+    let film = &props.film;
+    let watched_at = time_format::format_date(&time_format::date_from_iso(&film.watched_at));
+
+    let mut facts = vec![];
+    if let Some(year) = film.year {
+        facts.push(year.to_string());
+    }
+    if let Some(director) = &film.director {
+        facts.push(format!("Directed by {}", director));
+    }
+    if let Some(runtime) = film.runtime {
+        facts.push(format_runtime(runtime));
+    }
+
+    html! {
+        <div class="film-details">
+            <img class="film-details-poster" alt="Film poster art" src={film.poster_url.clone()}/>
+            <div class="film-details-info">
+                <h3>{ film.name.clone() }</h3>
+                if !facts.is_empty() {
+                    <p>{ facts.join(" · ") }</p>
+                }
+                <p>
+                    <span class="film-details-rating">{ format_stars(film.rating) }</span>
+                    if film.liked {
+                        <span class="film-details-liked" title="Liked">{ " ♥" }</span>
+                    }
+                </p>
+                <p>
+                    { if film.rewatch { "Rewatched on " } else { "Watched on " } }
+                    { watched_at }
+                </p>
+                <fieldset>
+                    <legend>{ "Review" }</legend>
+                    {
+                        match &film.review {
+                            Some(review) if !review.trim().is_empty() => review
+                                .split("\n\n")
+                                .map(|paragraph| html! { <p>{ paragraph }</p> })
+                                .collect::<Html>(),
+                            _ => html! { <p><i>{ "No review written." }</i></p> },
+                        }
+                    }
+                </fieldset>
+            </div>
+        </div>
+    }
+}
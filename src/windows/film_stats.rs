@@ -0,0 +1,411 @@
+use std::collections::BTreeMap;
+
+use yew::{function_component, html, Html, Properties};
+
+use super::films::{format_stars, Film};
+
+const WEEKDAYS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+const MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+/// A calendar date parsed from the start of an ISO 8601 string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct CivilDate {
+    pub year: i32,
+    pub month: u32,
+    pub day: u32,
+}
+impl CivilDate {
+    pub fn parse(iso: &str) -> Option<CivilDate> {
+        let year = iso.get(0..4)?.parse().ok()?;
+        let month = iso.get(5..7)?.parse().ok()?;
+        let day = iso.get(8..10)?.parse().ok()?;
+        if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+            return None;
+        }
+        Some(CivilDate { year, month, day })
+    }
+
+    /// Days since 1970-01-01, using Howard Hinnant's `days_from_civil`.
+    pub fn days_since_epoch(&self) -> i64 {
+        let year = if self.month <= 2 {
+            self.year as i64 - 1
+        } else {
+            self.year as i64
+        };
+        let era = (if year >= 0 { year } else { year - 399 }) / 400;
+        let year_of_era = year - era * 400;
+        let month = self.month as i64;
+        let day_of_year = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5
+            + self.day as i64
+            - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+        era * 146_097 + day_of_era - 719_468
+    }
+
+    /// Day of the week where 0 is Monday.
+    pub fn weekday(&self) -> usize {
+        // 1970-01-01 was a Thursday.
+        (self.days_since_epoch() + 3).rem_euclid(7) as usize
+    }
+}
+
+fn watched_dates(films: &[Film]) -> impl Iterator<Item = CivilDate> + '_ {
+    films.iter().filter_map(|f| CivilDate::parse(&f.watched_at))
+}
+
+/// Number of films watched in each month, oldest first, keyed by (year, month).
+pub fn films_per_month(films: &[Film]) -> Vec<((i32, u32), usize)> {
+    let mut months = BTreeMap::new();
+    for date in watched_dates(films) {
+        *months.entry((date.year, date.month)).or_insert(0) += 1;
+    }
+    months.into_iter().collect()
+}
+
+/// Number of films watched in each year, oldest first.
+pub fn films_per_year(films: &[Film]) -> Vec<(i32, usize)> {
+    let mut years = BTreeMap::new();
+    for date in watched_dates(films) {
+        *years.entry(date.year).or_insert(0) += 1;
+    }
+    years.into_iter().collect()
+}
+
+/// How many films received each rating from 0 (unrated) to 10 (five stars).
+pub fn rating_distribution(films: &[Film]) -> [usize; 11] {
+    let mut distribution = [0; 11];
+    for film in films {
+        distribution[film.rating.min(10) as usize] += 1;
+    }
+    distribution
+}
+
+/// Mean star rating out of five, ignoring unrated films.
+pub fn average_rating(films: &[Film]) -> Option<f64> {
+    let ratings: Vec<u32> = films.iter().map(|f| f.rating).filter(|r| *r > 0).collect();
+    if ratings.is_empty() {
+        None
+    } else {
+        Some(ratings.iter().sum::<u32>() as f64 / ratings.len() as f64 / 2.0)
+    }
+}
+
+/// Most consecutive days with at least one film watched.
+pub fn longest_streak(films: &[Film]) -> usize {
+    let mut days: Vec<i64> = watched_dates(films).map(|d| d.days_since_epoch()).collect();
+    days.sort_unstable();
+    days.dedup();
+
+    let mut longest = 0;
+    let mut current = 0;
+    let mut previous = None;
+    for day in days {
+        current = if previous == Some(day - 1) { current + 1 } else { 1 };
+        longest = longest.max(current);
+        previous = Some(day);
+    }
+    longest
+}
+
+/// Films watched on each weekday, Monday first.
+pub fn films_per_weekday(films: &[Film]) -> [usize; 7] {
+    let mut weekdays = [0; 7];
+    for date in watched_dates(films) {
+        weekdays[date.weekday()] += 1;
+    }
+    weekdays
+}
+
+/// The weekday with the most films watched, if any were.
+pub fn busiest_weekday(films: &[Film]) -> Option<usize> {
+    let weekdays = films_per_weekday(films);
+    (0..7)
+        .filter(|i| weekdays[*i] > 0)
+        .max_by_key(|i| (weekdays[*i], std::cmp::Reverse(*i)))
+}
+
+#[derive(Properties, PartialEq, Eq)]
+pub struct BarChartProps {
+    pub title: String,
+    pub bars: Vec<(String, usize)>,
+}
+
+/// A sunken 98.css style panel with a simple SVG bar chart inside.
+#[function_component(BarChart)]
+pub fn bar_chart(props: &BarChartProps) -> Html {
+    const BAR_WIDTH: usize = 24;
+    const GAP: usize = 4;
+    const HEIGHT: usize = 100;
+    const LABEL_HEIGHT: usize = 14;
+
+    let max = props.bars.iter().map(|(_, v)| *v).max().unwrap_or(0).max(1);
+    let width = (props.bars.len() * (BAR_WIDTH + GAP)).max(BAR_WIDTH);
+    let view_box = format!("0 0 {} {}", width, HEIGHT + LABEL_HEIGHT * 2);
+
+    html! {
+        <fieldset class="film-chart">
+            <legend>{ props.title.clone() }</legend>
+            <div class="film-chart-scroll">
+                <svg
+                    width={width.to_string()}
+                    height={(HEIGHT + LABEL_HEIGHT * 2).to_string()}
+                    viewBox={view_box}
+                    role="img"
+                    aria-label={props.title.clone()}
+                >
+                    {
+                        props.bars.iter().enumerate().map(|(i, (label, value))| {
+                            let bar_height = value * HEIGHT / max;
+                            let x = i * (BAR_WIDTH + GAP);
+                            let y = LABEL_HEIGHT + HEIGHT - bar_height;
+                            let centre = x + BAR_WIDTH / 2;
+                            html! {
+                                <g>
+                                    <title>{ format!("{}: {}", label, value) }</title>
+                                    <rect
+                                        class="film-chart-bar"
+                                        x={x.to_string()}
+                                        y={y.to_string()}
+                                        width={BAR_WIDTH.to_string()}
+                                        height={bar_height.to_string()}
+                                    />
+                                    if *value > 0 {
+                                        <text x={centre.to_string()} y={(y - 2).to_string()} text-anchor="middle">
+                                            { value }
+                                        </text>
+                                    }
+                                    <text
+                                        x={centre.to_string()}
+                                        y={(LABEL_HEIGHT * 2 + HEIGHT - 2).to_string()}
+                                        text-anchor="middle"
+                                    >
+                                        { label.clone() }
+                                    </text>
+                                </g>
+                            }
+                        }).collect::<Html>()
+                    }
+                </svg>
+            </div>
+        </fieldset>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct FilmStatsProps {
+    pub films: Vec<Film>,
+}
+
+#[function_component(FilmStats)]
+pub fn film_stats(props: &FilmStatsProps) -> Html {
+    let films = &props.films;
+
+    let per_year = films_per_year(films);
+    let latest_year = per_year.last().map(|(year, _)| *year);
+    let per_month = films_per_month(films)
+        .into_iter()
+        .filter(|((year, _), _)| Some(*year) == latest_year)
+        .map(|((_, month), count)| (MONTHS[month as usize - 1].to_string(), count))
+        .collect::<Vec<_>>();
+    let per_year = per_year
+        .into_iter()
+        .map(|(year, count)| (year.to_string(), count))
+        .collect::<Vec<_>>();
+    let ratings = rating_distribution(films)
+        .iter()
+        .enumerate()
+        .skip(1)
+        .map(|(rating, count)| (format!("{}", rating as f64 / 2.0), *count))
+        .collect::<Vec<_>>();
+    let weekdays = films_per_weekday(films)
+        .iter()
+        .enumerate()
+        .map(|(i, count)| (WEEKDAYS[i].to_string(), *count))
+        .collect::<Vec<_>>();
+
+    let average = average_rating(films)
+        .map(|average| format!("{:.2} ({})", average, format_stars((average * 2.0).round() as u32)))
+        .unwrap_or_else(|| "-".to_string());
+    let busiest = busiest_weekday(films)
+        .map(|i| WEEKDAYS[i])
+        .unwrap_or("-");
+
+    html! {
+        <div class="film-stats">
+            <ul class="tree-view">
+                <li><b>{ "Films logged: " }</b>{ films.len() }</li>
+                <li><b>{ "Average rating: " }</b>{ average }</li>
+                <li><b>{ "Longest streak: " }</b>{ format!("{} days", longest_streak(films)) }</li>
+                <li><b>{ "Favourite day to watch: " }</b>{ busiest }</li>
+            </ul>
+            if let Some(year) = latest_year {
+                <BarChart title={format!("Films per month in {}", year)} bars={per_month} />
+            }
+            <BarChart title="Films per year" bars={per_year} />
+            <BarChart title="Rating distribution" bars={ratings} />
+            <BarChart title="Films per weekday" bars={weekdays} />
+        </div>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn film(watched_at: &str, rating: u32) -> Film {
+        Film {
+            watched_at: watched_at.to_string(),
+            name: format!("Film watched {}", watched_at),
+            rating,
+            poster_url: String::new(),
+            rewatch: false,
+            liked: false,
+            review: None,
+            director: None,
+            year: None,
+            runtime: None,
+            guid: None,
+            duplicate: 0,
+        }
+    }
+
+    fn watched(dates: &[&str]) -> Vec<Film> {
+        dates.iter().map(|date| film(date, 0)).collect()
+    }
+
+    fn date(iso: &str) -> CivilDate {
+        CivilDate::parse(iso).unwrap()
+    }
+
+    #[test]
+    fn parses_dates() {
+        assert_eq!(
+            CivilDate::parse("2023-04-05T20:00:00Z"),
+            Some(CivilDate {
+                year: 2023,
+                month: 4,
+                day: 5
+            })
+        );
+        assert_eq!(CivilDate::parse("2023-13-01"), None);
+        assert_eq!(CivilDate::parse("2023-00-01"), None);
+        assert_eq!(CivilDate::parse("2023-01-32"), None);
+        assert_eq!(CivilDate::parse("2023-01"), None);
+        assert_eq!(CivilDate::parse(""), None);
+    }
+
+    #[test]
+    fn counts_days_and_weekdays() {
+        assert_eq!(date("1970-01-01").days_since_epoch(), 0);
+        assert_eq!(date("1969-12-31").days_since_epoch(), -1);
+        assert_eq!(date("2000-03-01").days_since_epoch(), 11_017);
+        // 2000 and 2024 were leap years, 1900 wasn't.
+        assert_eq!(
+            date("2024-03-01").days_since_epoch() - date("2024-02-28").days_since_epoch(),
+            2
+        );
+        assert_eq!(
+            date("2000-03-01").days_since_epoch() - date("2000-02-28").days_since_epoch(),
+            2
+        );
+        assert_eq!(
+            date("1900-03-01").days_since_epoch() - date("1900-02-28").days_since_epoch(),
+            1
+        );
+
+        assert_eq!(date("1970-01-01").weekday(), 3);
+        assert_eq!(date("2024-02-29").weekday(), 3);
+        assert_eq!(date("2023-01-01").weekday(), 6);
+        assert_eq!(date("1969-12-29").weekday(), 0);
+    }
+
+    #[test]
+    fn handles_no_films() {
+        assert!(films_per_month(&[]).is_empty());
+        assert!(films_per_year(&[]).is_empty());
+        assert_eq!(rating_distribution(&[]), [0; 11]);
+        assert_eq!(average_rating(&[]), None);
+        assert_eq!(longest_streak(&[]), 0);
+        assert_eq!(films_per_weekday(&[]), [0; 7]);
+        assert_eq!(busiest_weekday(&[]), None);
+    }
+
+    #[test]
+    fn groups_by_month_and_year() {
+        let films = watched(&[
+            "2023-12-31",
+            "2022-01-05",
+            "2023-12-01",
+            "2024-01-01",
+            "not a date",
+        ]);
+        assert_eq!(
+            films_per_month(&films),
+            [((2022, 1), 1), ((2023, 12), 2), ((2024, 1), 1)]
+        );
+        assert_eq!(films_per_year(&films), [(2022, 1), (2023, 2), (2024, 1)]);
+    }
+
+    #[test]
+    fn summarises_ratings() {
+        let films = [
+            film("2023-01-01", 0),
+            film("2023-01-02", 7),
+            film("2023-01-03", 10),
+        ];
+        let mut expected = [0; 11];
+        expected[0] = 1;
+        expected[7] = 1;
+        expected[10] = 1;
+        assert_eq!(rating_distribution(&films), expected);
+        // Unrated films don't drag the average down.
+        assert_eq!(average_rating(&films), Some(4.25));
+        assert_eq!(average_rating(&[film("2023-01-01", 0)]), None);
+    }
+
+    #[test]
+    fn finds_streaks_across_months_and_years() {
+        assert_eq!(longest_streak(&watched(&["2023-05-05"])), 1);
+        // Two films on the same day still count as one day.
+        assert_eq!(longest_streak(&watched(&["2023-05-05", "2023-05-05"])), 1);
+        assert_eq!(
+            longest_streak(&watched(&["2023-01-31", "2023-02-01", "2023-02-02"])),
+            3
+        );
+        assert_eq!(
+            longest_streak(&watched(&["2024-01-01", "2023-12-30", "2023-12-31"])),
+            3
+        );
+        assert_eq!(
+            longest_streak(&watched(&["2024-02-28", "2024-02-29", "2024-03-01"])),
+            3
+        );
+        // 2023 had no 29th of February.
+        assert_eq!(longest_streak(&watched(&["2023-02-28", "2023-03-01"])), 2);
+        assert_eq!(
+            longest_streak(&watched(&[
+                "2023-01-01",
+                "2023-01-02",
+                "2023-01-04",
+                "2023-01-05",
+                "2023-01-06",
+            ])),
+            3
+        );
+    }
+
+    #[test]
+    fn finds_the_busiest_weekday() {
+        // A Monday, two Fridays and a Sunday.
+        let films = watched(&["2024-01-01", "2024-01-05", "2024-01-12", "2024-01-07"]);
+        assert_eq!(films_per_weekday(&films), [1, 0, 0, 0, 2, 0, 1]);
+        assert_eq!(busiest_weekday(&films), Some(4));
+        // Ties go to the earlier day in the week.
+        assert_eq!(
+            busiest_weekday(&watched(&["2024-01-07", "2024-01-02"])),
+            Some(1)
+        );
+    }
+}
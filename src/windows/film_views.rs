@@ -0,0 +1,281 @@
+use gloo::storage::{LocalStorage, Storage};
+use serde::{Deserialize, Serialize};
+use web_sys::{Element, KeyboardEvent};
+use yew::{
+    classes, function_component, html, use_effect_with_deps, use_node_ref, use_state, Callback,
+    Html, NodeRef, Properties,
+};
+
+use super::films::{format_stars, Film, FilmComponent, SortBy};
+use crate::time_format;
+
+const VIEW_MODE_KEY: &str = "films-view-mode";
+
+/// Layouts offered by the View menu, like Explorer's.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum ViewMode {
+    LargeIcons,
+    List,
+    Details,
+}
+impl ViewMode {
+    pub const ALL: [ViewMode; 3] = [ViewMode::LargeIcons, ViewMode::List, ViewMode::Details];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ViewMode::LargeIcons => "Large Icons",
+            ViewMode::List => "List",
+            ViewMode::Details => "Details",
+        }
+    }
+
+    /// The visitor's last chosen view, defaulting to the poster wall.
+    pub fn load() -> ViewMode {
+        LocalStorage::get(VIEW_MODE_KEY).unwrap_or(ViewMode::LargeIcons)
+    }
+
+    pub fn save(&self) {
+        LocalStorage::set(VIEW_MODE_KEY, self).ok();
+    }
+}
+
+/// Items per row in the rendered list, so up/down arrows can skip a row.
+fn columns(container: &NodeRef) -> usize {
+    let container = match container.cast::<Element>() {
+        Some(container) => container,
+        None => return 1,
+    };
+    let children = container.children();
+    let first_top = match children.item(0) {
+        Some(first) => first.get_bounding_client_rect().top(),
+        None => return 1,
+    };
+    (0..children.length())
+        .filter_map(|i| children.item(i))
+        .take_while(|child| child.get_bounding_client_rect().top() == first_top)
+        .count()
+        .max(1)
+}
+
+#[derive(Properties, PartialEq)]
+pub struct FilmViewProps {
+    pub films: Vec<Film>,
+    pub mode: ViewMode,
+    pub sort: SortBy,
+    pub on_sort: Callback<SortBy>,
+    pub open_film: Callback<Film>,
+}
+
+/// The film list in whichever view mode is active, with arrow key navigation.
+#[function_component(FilmView)]
+pub fn film_view(props: &FilmViewProps) -> Html {
+    // By id rather than index, so the selection follows the film when the
+    // list is re-sorted or filtered and clears when it's filtered out.
+    let selected = use_state(|| None::<usize>);
+    let selected_index = selected.and_then(|id| props.films.iter().position(|f| f.id() == id));
+    let container = use_node_ref();
+
+    // Keep the selected item visible as it moves.
+    {
+        let container = container.clone();
+        use_effect_with_deps(
+            move |index| {
+                let item = index.and_then(|i| {
+                    container
+                        .cast::<Element>()
+                        .and_then(|c| c.children().item(i as u32))
+                });
+                if let Some(item) = item {
+                    item.scroll_into_view_with_bool(false);
+                }
+                || ()
+            },
+            selected_index,
+        );
+    }
+
+    let onkeydown = {
+        let selected = selected.clone();
+        let container = container.clone();
+        let films = props.films.clone();
+        let open_film = props.open_film.clone();
+        let mode = props.mode;
+        Callback::from(move |e: KeyboardEvent| {
+            if films.is_empty() {
+                return;
+            }
+            let last = films.len() - 1;
+            let row = if mode == ViewMode::LargeIcons {
+                columns(&container)
+            } else {
+                1
+            };
+            let current = selected_index;
+
+            let next = match e.key().as_str() {
+                "ArrowRight" if mode == ViewMode::LargeIcons => current.map_or(0, |i| (i + 1).min(last)),
+                "ArrowLeft" if mode == ViewMode::LargeIcons => current.map_or(0, |i| i.saturating_sub(1)),
+                "ArrowDown" => current.map_or(0, |i| (i + row).min(last)),
+                "ArrowUp" => current.map_or(0, |i| i.saturating_sub(row)),
+                "Home" => 0,
+                "End" => last,
+                "Enter" => {
+                    if let Some(film) = current.and_then(|i| films.get(i)) {
+                        open_film.emit(film.clone());
+                    }
+                    return;
+                }
+                _ => return,
+            };
+            e.prevent_default();
+            selected.set(Some(films[next].id()));
+        })
+    };
+
+    let select = |film: &Film| {
+        let selected = selected.clone();
+        let id = film.id();
+        Callback::from(move |_| selected.set(Some(id)))
+    };
+
+    let select_film = {
+        let selected = selected.clone();
+        Callback::from(move |film: Film| selected.set(Some(film.id())))
+    };
+
+    let body = match props.mode {
+        ViewMode::LargeIcons => html! {
+            <div class="film-list" ref={container.clone()}>
+                {
+                    props.films.iter().map(|film| html! {
+                        <FilmComponent
+                            key={film.id()}
+                            film={film.clone()}
+                            select_film={select_film.clone()}
+                            open_film={props.open_film.clone()}
+                            selected={*selected == Some(film.id())}
+                        />
+                    }).collect::<Html>()
+                }
+            </div>
+        },
+        ViewMode::List => html! {
+            <ul class="film-list-view" ref={container.clone()}>
+                {
+                    props.films.iter().map(|film| {
+                        let open = {
+                            let film = film.clone();
+                            let open_film = props.open_film.clone();
+                            Callback::from(move |_| open_film.emit(film.clone()))
+                        };
+                        html! {
+                            <li
+                                key={film.id()}
+                                class={classes!((*selected == Some(film.id())).then_some("selected"))}
+                                onclick={select(film)}
+                                ondblclick={open}
+                            >
+                                <img src="assets/icons/camera3_vid-2.png" alt="" />
+                                { film.name.clone() }
+                            </li>
+                        }
+                    }).collect::<Html>()
+                }
+            </ul>
+        },
+        ViewMode::Details => {
+            let header = |label: &str, ascending: SortBy, descending: SortBy| {
+                let on_sort = props.on_sort.clone();
+                let current = props.sort;
+                let next = if current == descending { ascending } else { descending };
+                let arrow = if current == ascending {
+                    " ▲"
+                } else if current == descending {
+                    " ▼"
+                } else {
+                    ""
+                };
+                html! {
+                    <th onclick={Callback::from(move |_| on_sort.emit(next))}>
+                        { label }{ arrow }
+                    </th>
+                }
+            };
+
+            html! {
+                <div class="film-table-container">
+                    <table class="film-table">
+                        <thead>
+                            <tr>
+                                { header("Title", SortBy::Title, SortBy::Title) }
+                                { header("Rating", SortBy::RatingLowest, SortBy::RatingHighest) }
+                                { header("Watched", SortBy::WatchedOldest, SortBy::WatchedNewest) }
+                            </tr>
+                        </thead>
+                        <tbody ref={container.clone()}>
+                            {
+                                props.films.iter().map(|film| {
+                                    let open = {
+                                        let film = film.clone();
+                                        let open_film = props.open_film.clone();
+                                        Callback::from(move |_| open_film.emit(film.clone()))
+                                    };
+                                    let watched_at = time_format::format_date(
+                                        &time_format::date_from_iso(&film.watched_at),
+                                    );
+                                    html! {
+                                        <tr
+                                            key={film.id()}
+                                            class={classes!((*selected == Some(film.id())).then_some("selected"))}
+                                            onclick={select(film)}
+                                            ondblclick={open}
+                                        >
+                                            <td>{ film.name.clone() }</td>
+                                            <td>{ format_stars(film.rating) }</td>
+                                            <td>{ watched_at }</td>
+                                        </tr>
+                                    }
+                                }).collect::<Html>()
+                            }
+                        </tbody>
+                    </table>
+                </div>
+            }
+        }
+    };
+
+    html! {
+        <div class="film-view" tabindex="0" {onkeydown}>
+            { body }
+        </div>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct ViewMenuProps {
+    pub mode: ViewMode,
+    pub on_change: Callback<ViewMode>,
+}
+
+#[function_component(ViewMenu)]
+pub fn view_menu(props: &ViewMenuProps) -> Html {
+    html! {
+        <div class="field-row film-view-menu">
+            <span>{ "View:" }</span>
+            {
+                ViewMode::ALL.iter().map(|mode| {
+                    let mode = *mode;
+                    let on_change = props.on_change.clone();
+                    html! {
+                        <button
+                            aria-pressed={(props.mode == mode).to_string()}
+                            onclick={Callback::from(move |_| on_change.emit(mode))}
+                        >
+                            { mode.label() }
+                        </button>
+                    }
+                }).collect::<Html>()
+            }
+        </div>
+    }
+}
@@ -1,89 +1,666 @@
+use std::cmp::Reverse;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
 use gloo::net::http::Request;
-use js_sys::Date;
+use gloo::timers::callback::Interval;
 use serde::{Deserialize};
-use wasm_bindgen::JsValue;
 use wasm_bindgen_futures::spawn_local;
+use web_sys::{HtmlInputElement, HtmlSelectElement};
 
 use yew::{
-    function_component, html, use_effect_with_deps, use_state, Html, Properties, UseStateHandle,
+    classes, function_component, html, use_effect_with_deps, use_state, Callback, Event, Html, InputEvent,
+    MouseEvent, Properties, TargetCast, UseStateHandle,
 };
 
-#[derive(Deserialize, Clone, Eq, PartialEq)]
-struct Film {
-    watched_at: String,
-    name: String,
-    rating: u32,
-    poster_url: String,
+use super::film_stats::FilmStats;
+use super::film_views::{FilmView, ViewMenu, ViewMode};
+use super::letterboxd;
+use crate::time_format;
+use crate::LETTERBOXD_FEED;
+
+const FILMS_URL: &str = "https://api.rovi.me/films";
+/// How often the list is re-fetched while the window stays open.
+const REFRESH_INTERVAL: u32 = 5 * 60 * 1000;
+
+#[derive(Deserialize, Clone, Eq, PartialEq, Debug)]
+pub struct Film {
+    pub watched_at: String,
+    pub name: String,
+    pub rating: u32,
+    pub poster_url: String,
+    // Optional so responses from before these were added still deserialise.
+    #[serde(default)]
+    pub rewatch: bool,
+    #[serde(default)]
+    pub liked: bool,
+    #[serde(default)]
+    pub review: Option<String>,
+    #[serde(default)]
+    pub director: Option<String>,
+    #[serde(default)]
+    pub year: Option<u32>,
+    /// Length of the film in minutes.
+    #[serde(default)]
+    pub runtime: Option<u32>,
+    /// The diary entry's unique id on Letterboxd.
+    #[serde(default)]
+    pub guid: Option<String>,
+    /// Counts earlier entries with the same name, date and guid, so logging
+    /// a film twice in a day still gives two ids. Set by `number_duplicates`.
+    #[serde(skip)]
+    pub duplicate: usize,
+}
+impl Film {
+    /// Stable identifier for this diary entry, used to key its details window.
+    pub fn id(&self) -> usize {
+        let mut hasher = DefaultHasher::new();
+        self.name.hash(&mut hasher);
+        self.watched_at.hash(&mut hasher);
+        self.guid.hash(&mut hasher);
+        self.duplicate.hash(&mut hasher);
+        hasher.finish() as usize
+    }
+
+    /// Year the film was watched, taken from the ISO `watched_at` date.
+    fn watched_year(&self) -> Option<u32> {
+        self.watched_at.get(..4).and_then(|year| year.parse().ok())
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum FilmsTab {
+    Diary,
+    Stats,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SortBy {
+    WatchedNewest,
+    WatchedOldest,
+    RatingHighest,
+    RatingLowest,
+    Title,
+}
+impl SortBy {
+    const ALL: [SortBy; 5] = [
+        SortBy::WatchedNewest,
+        SortBy::WatchedOldest,
+        SortBy::RatingHighest,
+        SortBy::RatingLowest,
+        SortBy::Title,
+    ];
+
+    fn label(&self) -> &'static str {
+        match self {
+            SortBy::WatchedNewest => "Newest first",
+            SortBy::WatchedOldest => "Oldest first",
+            SortBy::RatingHighest => "Highest rated",
+            SortBy::RatingLowest => "Lowest rated",
+            SortBy::Title => "Title",
+        }
+    }
+}
+
+/// Everything the toolbar can narrow or reorder the film list by.
+#[derive(Clone, PartialEq, Eq, Debug)]
+struct FilmQuery {
+    sort: SortBy,
+    min_rating: u32,
+    max_rating: u32,
+    year: Option<u32>,
+    search: String,
+}
+impl Default for FilmQuery {
+    fn default() -> Self {
+        FilmQuery {
+            sort: SortBy::WatchedNewest,
+            min_rating: 0,
+            max_rating: 10,
+            year: None,
+            search: String::new(),
+        }
+    }
+}
+impl FilmQuery {
+    fn matches(&self, film: &Film) -> bool {
+        let name = film.name.to_lowercase();
+        let search = self.search.to_lowercase();
+
+        film.rating >= self.min_rating
+            && film.rating <= self.max_rating
+            && (self.year.is_none() || film.watched_year() == self.year)
+            && search.split_whitespace().all(|term| name.contains(term))
+    }
+
+    /// Filters and sorts `films`, leaving the original list untouched.
+    fn apply(&self, films: &[Film]) -> Vec<Film> {
+        let mut films: Vec<Film> = films.iter().filter(|f| self.matches(f)).cloned().collect();
+        match self.sort {
+            // ISO dates sort correctly as plain strings.
+            SortBy::WatchedNewest => films.sort_by(|a, b| b.watched_at.cmp(&a.watched_at)),
+            SortBy::WatchedOldest => films.sort_by(|a, b| a.watched_at.cmp(&b.watched_at)),
+            SortBy::RatingHighest => films.sort_by_key(|f| Reverse(f.rating)),
+            SortBy::RatingLowest => films.sort_by_key(|f| f.rating),
+            SortBy::Title => films.sort_by_key(|f| f.name.to_lowercase()),
+        }
+        films
+    }
+}
+
+pub fn format_stars(rating: u32) -> String {
+    let stars = "★".repeat((rating / 2) as usize);
+    if rating % 2 == 1 {
+        format!("{}½", stars)
+    } else if stars.is_empty() {
+        "No rating".to_string()
+    } else {
+        stars
+    }
+}
+
+#[derive(Properties, PartialEq)]
+struct FilmToolbarProps {
+    query: FilmQuery,
+    years: Vec<u32>,
+    on_change: Callback<FilmQuery>,
+}
+
+#[function_component(FilmToolbar)]
+fn film_toolbar(props: &FilmToolbarProps) -> Html {
+    let select_value = |e: Event| e.target_unchecked_into::<HtmlSelectElement>().value();
+
+    let on_sort = {
+        let query = props.query.clone();
+        let on_change = props.on_change.clone();
+        Callback::from(move |e: Event| {
+            let index: usize = select_value(e).parse().unwrap_or_default();
+            on_change.emit(FilmQuery {
+                sort: SortBy::ALL[index.min(SortBy::ALL.len() - 1)],
+                ..query.clone()
+            });
+        })
+    };
+    let on_min_rating = {
+        let query = props.query.clone();
+        let on_change = props.on_change.clone();
+        Callback::from(move |e: Event| {
+            let min_rating = select_value(e).parse().unwrap_or_default();
+            on_change.emit(FilmQuery {
+                min_rating,
+                max_rating: query.max_rating.max(min_rating),
+                ..query.clone()
+            });
+        })
+    };
+    let on_max_rating = {
+        let query = props.query.clone();
+        let on_change = props.on_change.clone();
+        Callback::from(move |e: Event| {
+            let max_rating = select_value(e).parse().unwrap_or(10);
+            on_change.emit(FilmQuery {
+                min_rating: query.min_rating.min(max_rating),
+                max_rating,
+                ..query.clone()
+            });
+        })
+    };
+    let on_year = {
+        let query = props.query.clone();
+        let on_change = props.on_change.clone();
+        Callback::from(move |e: Event| {
+            on_change.emit(FilmQuery {
+                year: select_value(e).parse().ok(),
+                ..query.clone()
+            });
+        })
+    };
+    let on_search = {
+        let query = props.query.clone();
+        let on_change = props.on_change.clone();
+        Callback::from(move |e: InputEvent| {
+            on_change.emit(FilmQuery {
+                search: e.target_unchecked_into::<HtmlInputElement>().value(),
+                ..query.clone()
+            });
+        })
+    };
+
+    let rating_options = |selected: u32| {
+        (0..=10)
+            .map(|rating| html! {
+                <option value={rating.to_string()} selected={rating == selected}>
+                    { format_stars(rating) }
+                </option>
+            })
+            .collect::<Html>()
+    };
+
+    html! {
+        <div class="film-toolbar">
+            <div class="field-row">
+                <label for="film-search">{ "Search:" }</label>
+                <input
+                    id="film-search"
+                    type="text"
+                    placeholder="Film title"
+                    value={props.query.search.clone()}
+                    oninput={on_search}
+                />
+                <label for="film-sort">{ "Sort:" }</label>
+                <select id="film-sort" onchange={on_sort}>
+                    {
+                        SortBy::ALL.iter().enumerate().map(|(i, sort)| html! {
+                            <option value={i.to_string()} selected={*sort == props.query.sort}>
+                                { sort.label() }
+                            </option>
+                        }).collect::<Html>()
+                    }
+                </select>
+            </div>
+            <div class="field-row">
+                <label for="film-min-rating">{ "Rating:" }</label>
+                <select id="film-min-rating" onchange={on_min_rating}>
+                    { rating_options(props.query.min_rating) }
+                </select>
+                <label for="film-max-rating">{ "to" }</label>
+                <select id="film-max-rating" onchange={on_max_rating}>
+                    { rating_options(props.query.max_rating) }
+                </select>
+                <label for="film-year">{ "Year:" }</label>
+                <select id="film-year" onchange={on_year}>
+                    <option value="" selected={props.query.year.is_none()}>{ "All" }</option>
+                    {
+                        props.years.iter().map(|year| html! {
+                            <option value={year.to_string()} selected={props.query.year == Some(*year)}>
+                                { year }
+                            </option>
+                        }).collect::<Html>()
+                    }
+                </select>
+            </div>
+        </div>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct FilmsProps {
+    pub open_film: Callback<Film>,
+}
+
+/// Where the film list is in its lifecycle.
+#[derive(Clone, PartialEq, Eq)]
+enum LoadState {
+    Loading,
+    Loaded(Vec<Film>, FilmSource),
+    Empty,
+    /// The API is down and the Letterboxd snapshot has no films either.
+    FallbackEmpty,
+    Error(String),
+}
+
+/// Which backend the current film list came from.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum FilmSource {
+    Api,
+    Letterboxd,
+}
+impl FilmSource {
+    fn label(&self) -> &'static str {
+        match self {
+            FilmSource::Api => "api.rovi.me",
+            FilmSource::Letterboxd => "Letterboxd RSS",
+        }
+    }
+}
+
+async fn fetch_api_films() -> Result<Vec<Film>, String> {
+    let resp = Request::get(FILMS_URL)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    if !resp.ok() {
+        return Err(format!("{} {}", resp.status(), resp.status_text()));
+    }
+    resp.json().await.map_err(|e| e.to_string())
+}
+
+async fn fetch_letterboxd_films() -> Result<Vec<Film>, String> {
+    let resp = Request::get(LETTERBOXD_FEED)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    if !resp.ok() {
+        return Err(format!("{} {}", resp.status(), resp.status_text()));
+    }
+    let xml = resp.text().await.map_err(|e| e.to_string())?;
+    Ok(letterboxd::parse_rss(&xml))
+}
+
+/// Sets each film's `duplicate` so that every film gets a different id.
+fn number_duplicates(films: &mut [Film]) {
+    let mut seen = HashMap::new();
+    // Diaries list the newest first, so count from the end to keep new
+    // entries from changing the ids of older ones.
+    for film in films.iter_mut().rev() {
+        film.duplicate = 0;
+        let count = seen.entry(film.id()).or_insert(0);
+        film.duplicate = *count;
+        *count += 1;
+    }
+}
+
+/// Tries our own API first and falls back to the Letterboxd feed if it's down.
+async fn fetch_films() -> Result<(Vec<Film>, FilmSource), String> {
+    let (mut films, source) = match fetch_api_films().await {
+        Ok(films) => (films, FilmSource::Api),
+        Err(api_error) => {
+            log::error!("Couldn't load films from the API: {}", api_error);
+            let films = fetch_letterboxd_films()
+                .await
+                .map_err(|feed_error| format!("{} / {}", api_error, feed_error))?;
+            (films, FilmSource::Letterboxd)
+        }
+    };
+    number_duplicates(&mut films);
+    Ok((films, source))
+}
+
+/// Fetches the films into `state`. Background refreshes keep showing the
+/// current list and only log failures rather than replacing it with an error.
+fn load_films(state: UseStateHandle<LoadState>, background: bool) {
+    if !background {
+        state.set(LoadState::Loading);
+    }
+    spawn_local(async move {
+        match fetch_films().await {
+            Ok((films, FilmSource::Letterboxd)) if films.is_empty() && background => {
+                log::error!("Couldn't refresh films: the Letterboxd feed is empty")
+            }
+            Ok((films, FilmSource::Letterboxd)) if films.is_empty() => {
+                state.set(LoadState::FallbackEmpty)
+            }
+            Ok((films, _)) if films.is_empty() => state.set(LoadState::Empty),
+            Ok((films, source)) => state.set(LoadState::Loaded(films, source)),
+            Err(e) if background => log::error!("Couldn't refresh films: {}", e),
+            Err(e) => state.set(LoadState::Error(e)),
+        }
+    });
+}
+
+#[derive(Properties, PartialEq)]
+struct FilmErrorProps {
+    message: &'static str,
+    #[prop_or_default]
+    detail: Option<String>,
+    on_retry: Callback<MouseEvent>,
+}
+
+#[function_component(FilmError)]
+fn film_error(props: &FilmErrorProps) -> Html {
+    html! {
+        <div class="film-error">
+            <div class="film-error-message">
+                <img src="assets/icons/msg_error-0.png" alt="Error icon" />
+                <p>
+                    { props.message }
+                    if let Some(detail) = &props.detail {
+                        <br/>
+                        <small>{ detail.clone() }</small>
+                    }
+                </p>
+            </div>
+            <button onclick={props.on_retry.clone()}>{ "Retry" }</button>
+        </div>
+    }
 }
 
 #[function_component(Films)]
-pub fn films() -> Html {
-    let films: UseStateHandle<Vec<Film>> = use_state(|| vec![]);
+pub fn films(props: &FilmsProps) -> Html {
+    let state = use_state(|| LoadState::Loading);
+    let query = use_state(FilmQuery::default);
+    let tab = use_state(|| FilmsTab::Diary);
+    let view_mode = use_state(ViewMode::load);
 
     {
-        let films = films.clone();
+        let state = state.clone();
         use_effect_with_deps(
             move |_| {
-                let films = films.clone();
-                spawn_local(async move {
-                    let fetched_films: Vec<Film> = Request::get("https://api.rovi.me/films")
-                        .send()
-                        .await
-                        .unwrap()
-                        .json()
-                        .await
-                        .unwrap();
-
-                    films.set(fetched_films);
+                load_films(state.clone(), false);
+                let refresh = Interval::new(REFRESH_INTERVAL, move || {
+                    load_films(state.clone(), true)
                 });
-
-                || ()
+                move || drop(refresh)
             },
             (),
         );
     }
 
-    if films.is_empty() {
-        html! {
-            <p>{ "Loading..." }</p>
+    let retry = {
+        let state = state.clone();
+        Callback::from(move |_| load_films(state.clone(), false))
+    };
+    let (films, source) = match &*state {
+        LoadState::Loaded(films, source) => (films, source),
+        LoadState::Loading => {
+            return html! {
+                <p>{ "Loading..." }</p>
+            }
         }
-    } else {
+        LoadState::Empty => {
+            return html! {
+                <p>{ "No films logged yet, check back soon!" }</p>
+            }
+        }
+        LoadState::FallbackEmpty => {
+            return html! {
+                <FilmError
+                    message="My film diary is unreachable and the backup copy is empty."
+                    on_retry={retry}
+                />
+            }
+        }
+        LoadState::Error(error) => {
+            return html! {
+                <FilmError
+                    message="Couldn't load my films."
+                    detail={error.clone()}
+                    on_retry={retry}
+                />
+            }
+        }
+    };
+
+    let mut years: Vec<u32> = films.iter().filter_map(|f| f.watched_year()).collect();
+    years.sort_unstable_by(|a, b| b.cmp(a));
+    years.dedup();
+
+    let on_change = {
+        let query = query.clone();
+        Callback::from(move |new_query| query.set(new_query))
+    };
+    let shown = query.apply(films);
+
+    let on_view_mode = {
+        let view_mode = view_mode.clone();
+        Callback::from(move |mode: ViewMode| {
+            mode.save();
+            view_mode.set(mode);
+        })
+    };
+    let on_sort = {
+        let query = query.clone();
+        Callback::from(move |sort| {
+            query.set(FilmQuery {
+                sort,
+                ..(*query).clone()
+            })
+        })
+    };
+
+    let tab_button = |target: FilmsTab, label: &str| {
+        let tab = tab.clone();
+        let selected = *tab == target;
         html! {
-            <>
-                <h4>{"Films I've Watched"}</h4>
-                <div class="film-list">
-                    { (*films).clone().iter().map(|f| html! {
-                        <FilmComponent film={f.clone()} />
-                        }).collect::<Html>()
-                    }
-                </div>
-            </>
+            <button
+                role="tab"
+                aria-selected={selected.to_string()}
+                onclick={Callback::from(move |_| tab.set(target))}
+            >
+                { label }
+            </button>
         }
+    };
+
+    html! {
+        <>
+            <h4>{"Films I've Watched"}</h4>
+            <menu role="tablist" class="tabs">
+                { tab_button(FilmsTab::Diary, "Diary") }
+                { tab_button(FilmsTab::Stats, "Stats") }
+            </menu>
+            <FilmToolbar query={(*query).clone()} {years} {on_change} />
+            if *tab == FilmsTab::Diary {
+                <ViewMenu mode={*view_mode} on_change={on_view_mode} />
+                <FilmView
+                    films={shown.clone()}
+                    mode={*view_mode}
+                    sort={query.sort}
+                    {on_sort}
+                    open_film={props.open_film.clone()}
+                />
+            } else {
+                <FilmStats films={shown.clone()} />
+            }
+            <div class="status-bar">
+                <p class="status-bar-field">{ format!("{} of {} films", shown.len(), films.len()) }</p>
+                <p class="status-bar-field">{ format!("Source: {}", source.label()) }</p>
+            </div>
+        </>
     }
 }
 
-#[derive(Properties, PartialEq, Eq)]
+#[derive(Properties, PartialEq)]
 pub struct FilmComponentProps {
-    film: Film, 
+    pub film: Film,
+    pub select_film: Callback<Film>,
+    pub open_film: Callback<Film>,
+    #[prop_or_default]
+    pub selected: bool,
 }
 #[function_component(FilmComponent)]
 pub fn film(props: &FilmComponentProps) -> Html {
     let film = &props.film;
-    let whole_rating = film.rating / 2;
-    let stars = std::iter::repeat("★").take(whole_rating as usize).collect::<String>();
-    let half = film.rating % 2 == 1;
+    let watched_at = time_format::format_date(&time_format::date_from_iso(&film.watched_at));
 
-    let watched_at = Date::new(&JsValue::from(&film.watched_at))
-        .to_locale_date_string("en-GB", &JsValue::UNDEFINED);
+    let onclick = {
+        let film = film.clone();
+        let select_film = props.select_film.clone();
+        Callback::from(move |_| select_film.emit(film.clone()))
+    };
+    let ondblclick = {
+        let film = film.clone();
+        let open_film = props.open_film.clone();
+        Callback::from(move |_| open_film.emit(film.clone()))
+    };
 
     html! {
-        <div>
-            <img alt="Film poster art" src={film.poster_url.clone()}/>
+        <div class={classes!("film", props.selected.then_some("selected"))} {onclick} {ondblclick} title="Double-click to open details">
+            <Poster src={film.poster_url.clone()} />
             <div style="display:flex; justify-content: space-between; margin-bottom: 2px;">
-                <span>{stars}{half.then_some("½").unwrap_or("")}</span>
+                <span>{format_stars(film.rating)}</span>
                 <span>{watched_at}</span>
             </div>
             <span><b>{film.name.clone()}</b></span>
         </div>
     }
 }
+
+#[derive(Properties, PartialEq, Eq)]
+pub struct PosterProps {
+    pub src: String,
+}
+
+/// A lazily loaded poster inside a sunken frame that holds its space until
+/// the image arrives.
+#[function_component(Poster)]
+pub fn poster(props: &PosterProps) -> Html {
+    let loaded = use_state(|| false);
+    let failed = use_state(|| false);
+
+    let onload = {
+        let loaded = loaded.clone();
+        Callback::from(move |_| loaded.set(true))
+    };
+    let onerror = {
+        let failed = failed.clone();
+        Callback::from(move |_| failed.set(true))
+    };
+
+    html! {
+        <div class="film-poster">
+            if *failed {
+                <span class="film-poster-missing">{ "No poster" }</span>
+            } else {
+                <img
+                    class={classes!((*loaded).then_some("loaded"))}
+                    alt="Film poster art"
+                    loading="lazy"
+                    src={props.src.clone()}
+                    {onload}
+                    {onerror}
+                />
+            }
+        </div>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn film(name: &str, watched_at: &str, guid: Option<&str>) -> Film {
+        serde_json::from_value(serde_json::json!({
+            "name": name,
+            "watched_at": watched_at,
+            "rating": 8,
+            "poster_url": "",
+            "guid": guid,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn gives_every_entry_its_own_id() {
+        let mut films = vec![
+            film("Paddington 2", "2024-09-28", None),
+            film("Paddington 2", "2024-09-28", None),
+            film("Aftersun", "2024-08-31", Some("letterboxd-review-2")),
+            film("Aftersun", "2024-08-31", Some("letterboxd-review-1")),
+        ];
+        number_duplicates(&mut films);
+
+        let mut ids: Vec<usize> = films.iter().map(Film::id).collect();
+        ids.sort_unstable();
+        ids.dedup();
+        assert_eq!(ids.len(), films.len());
+        assert_eq!(films[1].duplicate, 0);
+        assert_eq!(films[0].duplicate, 1);
+    }
+
+    #[test]
+    fn keeps_ids_when_newer_entries_arrive() {
+        let mut films = vec![
+            film("Paddington 2", "2024-09-28", None),
+            film("Paddington 2", "2024-09-28", None),
+        ];
+        number_duplicates(&mut films);
+        let before: Vec<usize> = films.iter().map(Film::id).collect();
+
+        films.insert(0, film("Paddington 2", "2024-09-28", None));
+        number_duplicates(&mut films);
+        assert_eq!(films[1].id(), before[0]);
+        assert_eq!(films[2].id(), before[1]);
+    }
+}
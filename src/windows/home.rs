@@ -10,6 +10,7 @@ pub struct HomeWindowProps {
     pub open_projects: Callback<MouseEvent>,
     pub open_films: Callback<MouseEvent>,
     pub open_photo_viewer: Callback<MouseEvent>,
+    pub open_status: Callback<MouseEvent>,
 }
 
 #[function_component(Home)]
@@ -22,6 +23,7 @@ pub fn home(props: &HomeWindowProps) -> Html {
             <ul>
                 <li><a href="javascript:void(0);" onclick={&props.open_about_me}>{ "About me" }</a></li>
                 <li><a href="javascript:void(0);" onclick={&props.open_spotify}>{ "See what I'm listening to on Spotify!" }</a></li>
+                <li><a href="javascript:void(0);" onclick={&props.open_status}>{ "What am I up to on Discord?" }</a></li>
                 <li><a href="javascript:void(0);" onclick={&props.open_background}>{ "Change the background?" }</a></li>
                 <li><a href="javascript:void(0);" onclick={&props.open_projects}>{ "My other projects.." }</a></li>
                 <li><a href="javascript:void(0);" onclick={&props.open_films}>{ "Check out my letterboxd" }</a></li>
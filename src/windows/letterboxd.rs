@@ -0,0 +1,191 @@
+use super::films::Film;
+
+/// Decodes the handful of XML/HTML entities Letterboxd emits.
+fn decode_entities(text: &str) -> String {
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let end = match rest.find(';') {
+            Some(end) if end <= 10 => end,
+            _ => {
+                decoded.push('&');
+                rest = &rest[1..];
+                continue;
+            }
+        };
+        let entity = &rest[1..end];
+        let character = match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "nbsp" => Some(' '),
+            _ => entity
+                .strip_prefix("#x")
+                .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                .or_else(|| entity.strip_prefix('#').and_then(|dec| dec.parse().ok()))
+                .and_then(char::from_u32),
+        };
+
+        match character {
+            Some(character) => {
+                decoded.push(character);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+/// Inner text of the first `<tag>` in `xml`, unwrapping CDATA sections.
+fn tag_text<'a>(xml: &'a str, tag: &str) -> Option<&'a str> {
+    let open = format!("<{}", tag);
+    let close = format!("</{}>", tag);
+
+    let start = xml.find(&open)?;
+    let after_open = &xml[start + open.len()..];
+    // Make sure we matched `<tag>` or `<tag attr=...>` rather than `<tagSomething>`.
+    if !after_open.starts_with('>') && !after_open.starts_with(' ') {
+        return tag_text(after_open, tag);
+    }
+    let content_start = after_open.find('>')? + 1;
+    let content = &after_open[content_start..];
+    let content = &content[..content.find(&close)?];
+
+    let content = content.trim();
+    Some(
+        content
+            .strip_prefix("<![CDATA[")
+            .and_then(|c| c.strip_suffix("]]>"))
+            .unwrap_or(content),
+    )
+}
+
+/// Removes HTML tags, keeping only the text between them.
+fn strip_tags(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            _ if !in_tag => text.push(c),
+            _ => (),
+        }
+    }
+    decode_entities(text.trim())
+}
+
+/// Converts a Letterboxd star rating such as `3.5` to our 0-10 scale.
+pub fn rating_from_stars(stars: &str) -> u32 {
+    stars
+        .trim()
+        .parse::<f64>()
+        .map(|stars| (stars * 2.0).round().clamp(0.0, 10.0) as u32)
+        .unwrap_or(0)
+}
+
+/// Splits a diary entry's description into its poster URL and review text.
+///
+/// Letterboxd puts the poster in the first paragraph as an `<img>` and adds a
+/// "Watched on ..." paragraph when there is no review.
+fn parse_description(description: &str) -> (Option<String>, Option<String>) {
+    let poster = description.find("src=\"").and_then(|start| {
+        let src = &description[start + 5..];
+        src.find('"').map(|end| decode_entities(&src[..end]))
+    });
+
+    let paragraphs: Vec<String> = description
+        .split("<p>")
+        .filter(|p| !p.contains("<img"))
+        .map(strip_tags)
+        .filter(|p| !p.is_empty() && !p.starts_with("Watched on "))
+        .collect();
+    let review = (!paragraphs.is_empty()).then(|| paragraphs.join("\n\n"));
+
+    (poster, review)
+}
+
+fn parse_item(item: &str) -> Option<Film> {
+    let name = decode_entities(tag_text(item, "letterboxd:filmTitle")?);
+    let watched_at = tag_text(item, "letterboxd:watchedDate")?.to_string();
+    let (poster_url, review) = tag_text(item, "description")
+        .map(parse_description)
+        .unwrap_or_default();
+    let yes = |tag: &str| tag_text(item, tag).is_some_and(|v| v.eq_ignore_ascii_case("yes"));
+
+    Some(Film {
+        watched_at,
+        name,
+        rating: tag_text(item, "letterboxd:memberRating")
+            .map(rating_from_stars)
+            .unwrap_or(0),
+        poster_url: poster_url.unwrap_or_default(),
+        rewatch: yes("letterboxd:rewatch"),
+        liked: yes("letterboxd:memberLike"),
+        review,
+        director: None,
+        year: tag_text(item, "letterboxd:filmYear").and_then(|y| y.parse().ok()),
+        runtime: None,
+        guid: tag_text(item, "guid").map(str::to_string),
+        duplicate: 0,
+    })
+}
+
+/// Parses a Letterboxd RSS feed into diary entries, skipping lists and any
+/// items that aren't film logs.
+pub fn parse_rss(xml: &str) -> Vec<Film> {
+    xml.split("<item>")
+        .skip(1)
+        .filter_map(|item| item.split("</item>").next())
+        .filter_map(parse_item)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_diary_entries() {
+        let films = parse_rss(include_str!("../../tests/fixtures/letterboxd.xml"));
+        // The list at the end isn't a diary entry.
+        assert_eq!(films.len(), 4);
+
+        let first = &films[0];
+        assert_eq!(first.name, "Sample Film One");
+        assert_eq!(first.watched_at, "2024-11-02");
+        assert_eq!(first.rating, 9);
+        assert_eq!(first.year, Some(2023));
+        assert!(first.liked && !first.rewatch);
+        assert_eq!(first.poster_url, "https://example.com/poster-1.jpg");
+        assert_eq!(
+            first.review.as_deref(),
+            Some("A sample review, for testing.")
+        );
+        assert_eq!(first.guid.as_deref(), Some("letterboxd-review-1"));
+
+        // "Watched on ..." isn't a review.
+        assert_eq!(films[1].review, None);
+        assert!(films[2].rewatch);
+        assert_eq!(
+            films[2].review.as_deref(),
+            Some("Reviews can use entities & quotes like it's fine.")
+        );
+        assert_eq!(films[3].rating, 5);
+        assert_eq!(
+            films[3].review.as_deref(),
+            Some("First paragraph.\n\nSecond paragraph.")
+        );
+    }
+}
@@ -0,0 +1,312 @@
+use std::cell::Cell;
+use std::rc::Rc;
+
+use gloo::net::http::Request;
+use gloo::timers::callback::Interval;
+use js_sys::Date;
+use serde::Deserialize;
+use urlencoding::encode;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::spawn_local;
+use web_sys::HtmlElement;
+use yew::{
+    classes, function_component, html, use_effect_with_deps, use_node_ref, use_state, Html,
+    Properties,
+};
+
+use crate::LYRICS_ENDPOINT;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LyricLine {
+    /// Offset from the start of the track in milliseconds.
+    pub time: u64,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum LyricsState {
+    Loading,
+    Synced(Vec<LyricLine>),
+    Plain(String),
+    Missing,
+}
+
+/// Response shape of an lrclib compatible lyrics endpoint.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LyricsResponse {
+    synced_lyrics: Option<String>,
+    plain_lyrics: Option<String>,
+}
+
+/// Parses an LRC timestamp such as `01:23.45`, `01:23.456` or `01:23` into milliseconds.
+fn parse_timestamp(tag: &str) -> Option<u64> {
+    let (minutes, seconds) = tag.split_once(':')?;
+    let minutes: u64 = minutes.trim().parse().ok()?;
+    let (seconds, fraction) = seconds.split_once('.').unwrap_or((seconds, ""));
+    let seconds: u64 = seconds.trim().parse().ok()?;
+    let fraction = match fraction.len() {
+        0 => 0,
+        1 => fraction.parse::<u64>().ok()? * 100,
+        2 => fraction.parse::<u64>().ok()? * 10,
+        _ => fraction.get(..3)?.parse().ok()?,
+    };
+    Some(minutes * 60_000 + seconds * 1_000 + fraction)
+}
+
+/// Parses LRC formatted lyrics into lines sorted by time.
+///
+/// Lines may carry several timestamps (`[00:12.00][00:45.10]chorus`), metadata
+/// tags like `[ar:...]` are skipped and an `[offset:...]` tag shifts every line.
+pub fn parse_lrc(lrc: &str) -> Vec<LyricLine> {
+    let mut offset: i64 = 0;
+    let mut lines = vec![];
+
+    for raw_line in lrc.lines() {
+        let mut rest = raw_line.trim();
+        let mut times = vec![];
+
+        while let Some(tag) = rest.strip_prefix('[') {
+            let (tag, remaining) = match tag.split_once(']') {
+                Some(split) => split,
+                None => break,
+            };
+            if let Some(time) = parse_timestamp(tag) {
+                times.push(time);
+            } else if let Some(value) = tag.strip_prefix("offset:") {
+                offset = value.trim().parse().unwrap_or(0);
+            }
+            rest = remaining;
+        }
+
+        let text = rest.trim().to_string();
+        lines.extend(times.into_iter().map(|time| LyricLine {
+            text: text.clone(),
+            time,
+        }));
+    }
+
+    // A positive offset means lyrics should appear sooner.
+    for line in lines.iter_mut() {
+        line.time = (line.time as i64 - offset).max(0) as u64;
+    }
+    lines.sort_by_key(|line| line.time);
+    lines
+}
+
+/// Index of the line being sung at `position` milliseconds into the track.
+pub fn current_line(lines: &[LyricLine], position: u64) -> Option<usize> {
+    lines.iter().rposition(|line| line.time <= position)
+}
+
+#[derive(Properties, PartialEq, Eq)]
+pub struct LyricsProps {
+    pub song: String,
+    pub artist: String,
+    pub album: String,
+    /// Unix milliseconds the track started playing, if known.
+    pub start_time: Option<u64>,
+    /// Unix milliseconds the track will finish, if known.
+    pub end_time: Option<u64>,
+}
+
+#[function_component(Lyrics)]
+pub fn lyrics(props: &LyricsProps) -> Html {
+    let lyrics = use_state(|| LyricsState::Loading);
+    let now = use_state(Date::now);
+    let container = use_node_ref();
+
+    {
+        let lyrics = lyrics.clone();
+        let duration = props
+            .start_time
+            .zip(props.end_time)
+            .map(|(start, end)| end.saturating_sub(start) / 1000);
+        use_effect_with_deps(
+            move |(song, artist, album)| {
+                lyrics.set(LyricsState::Loading);
+
+                let mut url = format!(
+                    "{}?track_name={}&artist_name={}&album_name={}",
+                    LYRICS_ENDPOINT,
+                    encode(song),
+                    encode(artist),
+                    encode(album)
+                );
+                if let Some(duration) = duration {
+                    url.push_str(&format!("&duration={}", duration));
+                }
+
+                // Set when the song changes, so a slow answer for the last
+                // one doesn't replace this one's lyrics.
+                let cancelled = Rc::new(Cell::new(false));
+                let request_cancelled = cancelled.clone();
+                spawn_local(async move {
+                    let response = match Request::get(&url).send().await {
+                        Ok(resp) if resp.ok() => resp.json::<LyricsResponse>().await.ok(),
+                        _ => None,
+                    };
+
+                    let (synced, plain) = response
+                        .map(|r| (r.synced_lyrics, r.plain_lyrics))
+                        .unwrap_or_default();
+                    let synced = synced.map(|lrc| parse_lrc(&lrc)).unwrap_or_default();
+
+                    if request_cancelled.get() {
+                        return;
+                    }
+                    if !synced.is_empty() {
+                        lyrics.set(LyricsState::Synced(synced));
+                    } else if let Some(plain) = plain.filter(|p| !p.trim().is_empty()) {
+                        lyrics.set(LyricsState::Plain(plain));
+                    } else {
+                        lyrics.set(LyricsState::Missing);
+                    }
+                });
+
+                move || cancelled.set(true)
+            },
+            (props.song.clone(), props.artist.clone(), props.album.clone()),
+        );
+    }
+
+    {
+        let now = now.clone();
+        use_effect_with_deps(
+            move |_| {
+                let interval = Interval::new(250, move || now.set(Date::now()));
+                move || drop(interval)
+            },
+            (),
+        );
+    }
+
+    let position = props
+        .start_time
+        .map(|start| (*now as u64).saturating_sub(start));
+    let current = match (&*lyrics, position) {
+        (LyricsState::Synced(lines), Some(position)) => current_line(lines, position),
+        _ => None,
+    };
+
+    {
+        let container = container.clone();
+        use_effect_with_deps(
+            move |current| {
+                if let (Some(current), Some(container)) = (current, container.cast::<HtmlElement>())
+                {
+                    let line = container
+                        .children()
+                        .item(*current as u32)
+                        .and_then(|line| line.dyn_into::<HtmlElement>().ok());
+                    // The container is positioned so offsets are relative to it.
+                    if let Some(line) = line {
+                        container.set_scroll_top(line.offset_top() - container.client_height() / 2);
+                    }
+                }
+                || ()
+            },
+            current,
+        );
+    }
+
+    let body = match &*lyrics {
+        LyricsState::Loading => html! { <p>{ "Loading lyrics..." }</p> },
+        LyricsState::Missing => html! { <p>{ "No lyrics found for this track." }</p> },
+        LyricsState::Plain(text) => text
+            .lines()
+            .map(|line| html! { <p>{ line }</p> })
+            .collect::<Html>(),
+        LyricsState::Synced(lines) => lines
+            .iter()
+            .enumerate()
+            .map(|(i, line)| {
+                let active = (Some(i) == current).then_some("active");
+                let text = if line.text.is_empty() { "♪" } else { line.text.as_str() };
+                html! { <p class={classes!(active)}>{ text }</p> }
+            })
+            .collect::<Html>(),
+    };
+
+    html! {
+        <div class="lyrics-container" ref={container}>
+            { body }
+        </div>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(time: u64, text: &str) -> LyricLine {
+        LyricLine {
+            time,
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn parses_timestamps() {
+        assert_eq!(parse_timestamp("01:23.45"), Some(83_450));
+        assert_eq!(parse_timestamp("01:23.456"), Some(83_456));
+        assert_eq!(parse_timestamp("01:23.4"), Some(83_400));
+        assert_eq!(parse_timestamp("01:23"), Some(83_000));
+        assert_eq!(parse_timestamp("10:00.00"), Some(600_000));
+        // Anything past milliseconds is dropped.
+        assert_eq!(parse_timestamp("00:01.23456"), Some(1_234));
+    }
+
+    #[test]
+    fn rejects_malformed_timestamps() {
+        assert_eq!(parse_timestamp("ar:Artist"), None);
+        assert_eq!(parse_timestamp("01-23.45"), None);
+        assert_eq!(parse_timestamp("01:2x.45"), None);
+        assert_eq!(parse_timestamp("01:23.ab"), None);
+        assert_eq!(parse_timestamp(":23.45"), None);
+        assert_eq!(parse_timestamp(""), None);
+    }
+
+    #[test]
+    fn repeats_lines_with_several_timestamps() {
+        let lines = parse_lrc("[00:12.00][00:45.10]chorus\n[00:20.00]verse");
+        assert_eq!(
+            lines,
+            vec![
+                line(12_000, "chorus"),
+                line(20_000, "verse"),
+                line(45_100, "chorus")
+            ]
+        );
+    }
+
+    #[test]
+    fn skips_metadata_and_applies_the_offset() {
+        let lrc =
+            "[ar:Some Artist]\n[ti:Some Song]\n[offset:500]\n[00:01.00]first\n[00:00.20]early";
+        assert_eq!(parse_lrc(lrc), vec![line(0, "early"), line(500, "first")]);
+
+        // A negative offset makes lyrics later.
+        assert_eq!(
+            parse_lrc("[offset:-250]\n[00:01]late"),
+            vec![line(1_250, "late")]
+        );
+    }
+
+    #[test]
+    fn ignores_malformed_lines() {
+        let lrc =
+            "no timestamp\n[00:0x.00]bad time\n[00:03.00 unclosed\n\n[00:04.00]good\n[00:05.00]";
+        assert_eq!(parse_lrc(lrc), vec![line(4_000, "good"), line(5_000, "")]);
+        assert_eq!(parse_lrc(""), vec![]);
+    }
+
+    #[test]
+    fn finds_the_current_line() {
+        let lines = parse_lrc("[00:01.00]one\n[00:02.00]two\n[00:03.00]three");
+        assert_eq!(current_line(&lines, 0), None);
+        assert_eq!(current_line(&lines, 1_000), Some(0));
+        assert_eq!(current_line(&lines, 2_500), Some(1));
+        assert_eq!(current_line(&lines, 60_000), Some(2));
+    }
+}
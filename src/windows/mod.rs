@@ -4,23 +4,51 @@ pub use home::Home;
 mod spotify;
 pub use spotify::Spotify;
 
+mod lyrics;
+mod track_progress;
+
 mod about_me;
 pub use about_me::AboutMe;
 
-mod background_selector;
-pub use background_selector::BackgroundSelector;
+mod display_properties;
+pub use display_properties::DisplayProperties;
+
+mod screensaver;
+pub use screensaver::ScreenSaver;
 
 mod socials;
 pub use socials::Socials;
 
+mod project_readme;
+pub use project_readme::ProjectReadme;
+mod project_views;
 mod projects;
-pub use projects::Projects;
+pub use projects::{ProjectData, Projects};
 
 mod sticky_note;
 pub use sticky_note::StickyNote;
 
 mod films;
-pub use films::Films;
+pub use films::{Film, Films};
+
+mod film_stats;
+mod film_views;
+mod letterboxd;
+
+mod film_details;
+pub use film_details::FilmDetails;
+
+mod photo_gallery;
+pub use photo_gallery::Photo;
+
+mod photo_editor;
+mod photo_properties;
+pub use photo_properties::PhotoProperties;
 
 mod photo_viewer;
+mod photo_zoom;
+mod responsive_image;
 pub use photo_viewer::PhotoViewer;
+
+mod status;
+pub use status::Status;
@@ -0,0 +1,369 @@
+use gloo::utils::document;
+use wasm_bindgen::{Clamped, JsCast};
+use wasm_bindgen_futures::spawn_local;
+use web_sys::{
+    CanvasRenderingContext2d, HtmlCanvasElement, HtmlElement, HtmlInputElement, ImageData,
+};
+use yew::prelude::*;
+
+use crate::colour::load_image;
+use crate::image_ops::{win98_palette, Image, Rect};
+
+/// Edits kept for Undo; photos are big so this stays small.
+const MAX_HISTORY: usize = 10;
+
+pub enum Msg {
+    Loaded(Option<Image>),
+    RotateClockwise,
+    RotateAnticlockwise,
+    FlipHorizontal,
+    FlipVertical,
+    Greyscale,
+    Dither,
+    Crop,
+    SetBrightness(i32),
+    SetContrast(i32),
+    SelectStart(MouseEvent),
+    SelectMove(MouseEvent),
+    SelectEnd,
+    Undo,
+    Revert,
+    Download,
+}
+
+#[derive(Properties, PartialEq)]
+pub struct PhotoEditorProps {
+    pub src: String,
+    /// Used to name the downloaded PNG.
+    pub title: String,
+    pub on_close: Callback<()>,
+}
+
+/// Draws `image` at full size onto `canvas`.
+fn draw(canvas: &HtmlCanvasElement, image: &Image) -> Option<()> {
+    canvas.set_width(image.width);
+    canvas.set_height(image.height);
+    let context: CanvasRenderingContext2d = canvas.get_context("2d").ok()??.dyn_into().ok()?;
+    let data = ImageData::new_with_u8_clamped_array_and_sh(
+        Clamped(&image.data),
+        image.width,
+        image.height,
+    )
+    .ok()?;
+    context.put_image_data(&data, 0.0, 0.0).ok()
+}
+
+async fn read_pixels(url: &str) -> Option<Image> {
+    let element = load_image(url).await?;
+    let (width, height) = (element.natural_width(), element.natural_height());
+
+    let canvas: HtmlCanvasElement = document().create_element("canvas").ok()?.dyn_into().ok()?;
+    canvas.set_width(width);
+    canvas.set_height(height);
+    let context: CanvasRenderingContext2d = canvas.get_context("2d").ok()??.dyn_into().ok()?;
+    context
+        .draw_image_with_html_image_element(&element, 0.0, 0.0)
+        .ok()?;
+    let data = context
+        .get_image_data(0.0, 0.0, width as f64, height as f64)
+        .ok()?
+        .data();
+    Image::new(width, height, data.0)
+}
+
+/// Kodak Imaging style tools for the current photo, drawn on a canvas.
+pub struct PhotoEditor {
+    canvas: NodeRef,
+    /// Every edit so far, the last being what's shown.
+    history: Vec<Image>,
+    failed: bool,
+    /// Previewed on top of the last edit until another tool bakes them in.
+    brightness: i32,
+    contrast: i32,
+    selection: Option<Rect>,
+    drag_start: Option<(u32, u32)>,
+    /// Bumped whenever `history` changes, so the canvas is only redrawn when
+    /// the picture has actually changed.
+    revision: usize,
+    drawn: Option<(usize, i32, i32)>,
+}
+
+impl PhotoEditor {
+    fn current(&self) -> Option<&Image> {
+        self.history.last()
+    }
+
+    /// The last edit with any pending brightness and contrast applied.
+    fn adjusted(&self) -> Option<Image> {
+        self.current()
+            .map(|image| image.adjust(self.brightness, self.contrast))
+    }
+
+    fn apply(&mut self, edit: impl FnOnce(&Image) -> Option<Image>) -> bool {
+        let edited = match self.adjusted().as_ref().and_then(edit) {
+            Some(edited) => edited,
+            None => return false,
+        };
+        self.history.push(edited);
+        self.revision += 1;
+        if self.history.len() > MAX_HISTORY {
+            // Keep the original so Revert always works.
+            self.history.remove(1);
+        }
+        self.brightness = 0;
+        self.contrast = 0;
+        self.selection = None;
+        true
+    }
+
+    /// Converts a mouse position to pixel coordinates on the canvas.
+    fn image_point(&self, e: &MouseEvent) -> Option<(u32, u32)> {
+        let canvas = self.canvas.cast::<HtmlCanvasElement>()?;
+        let rect = canvas.get_bounding_client_rect();
+        if rect.width() == 0.0 || rect.height() == 0.0 {
+            return None;
+        }
+        let x = (e.client_x() as f64 - rect.left()) / rect.width() * canvas.width() as f64;
+        let y = (e.client_y() as f64 - rect.top()) / rect.height() * canvas.height() as f64;
+        Some((
+            x.clamp(0.0, canvas.width() as f64) as u32,
+            y.clamp(0.0, canvas.height() as f64) as u32,
+        ))
+    }
+
+    fn download(&self, title: &str) -> Option<()> {
+        let canvas = self.canvas.cast::<HtmlCanvasElement>()?;
+        let url = canvas.to_data_url().ok()?;
+        let link: HtmlElement = document().create_element("a").ok()?.dyn_into().ok()?;
+        link.set_attribute("href", &url).ok()?;
+        link.set_attribute("download", &format!("{} (edited).png", title))
+            .ok()?;
+        link.click();
+        Some(())
+    }
+}
+
+impl Component for PhotoEditor {
+    type Message = Msg;
+    type Properties = PhotoEditorProps;
+
+    fn create(ctx: &Context<Self>) -> Self {
+        let src = ctx.props().src.clone();
+        let on_loaded = ctx.link().callback(Msg::Loaded);
+        spawn_local(async move { on_loaded.emit(read_pixels(&src).await) });
+
+        Self {
+            canvas: NodeRef::default(),
+            history: Vec::new(),
+            failed: false,
+            brightness: 0,
+            contrast: 0,
+            selection: None,
+            drag_start: None,
+            revision: 0,
+            drawn: None,
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Msg::Loaded(Some(image)) => {
+                self.history = vec![image];
+                self.revision += 1;
+                true
+            }
+            Msg::Loaded(None) => {
+                self.failed = true;
+                true
+            }
+            Msg::RotateClockwise => self.apply(|image| Some(image.rotate_clockwise())),
+            Msg::RotateAnticlockwise => self.apply(|image| Some(image.rotate_anticlockwise())),
+            Msg::FlipHorizontal => self.apply(|image| Some(image.flip_horizontal())),
+            Msg::FlipVertical => self.apply(|image| Some(image.flip_vertical())),
+            Msg::Greyscale => self.apply(|image| Some(image.greyscale())),
+            Msg::Dither => {
+                let palette = win98_palette();
+                self.apply(|image| Some(image.dither(&palette)))
+            }
+            Msg::Crop => match self.selection {
+                Some(selection) => self.apply(|image| image.crop(selection)),
+                None => false,
+            },
+            Msg::SetBrightness(brightness) => {
+                self.brightness = brightness;
+                true
+            }
+            Msg::SetContrast(contrast) => {
+                self.contrast = contrast;
+                true
+            }
+            Msg::SelectStart(e) => {
+                e.prevent_default();
+                self.drag_start = self.image_point(&e);
+                self.selection = None;
+                true
+            }
+            Msg::SelectMove(e) => match (self.drag_start, self.image_point(&e)) {
+                (Some((start_x, start_y)), Some((x, y))) => {
+                    self.selection = Some(Rect {
+                        x: start_x.min(x),
+                        y: start_y.min(y),
+                        width: start_x.abs_diff(x),
+                        height: start_y.abs_diff(y),
+                    })
+                    .filter(|r| r.width > 0 && r.height > 0);
+                    true
+                }
+                _ => false,
+            },
+            Msg::SelectEnd => {
+                self.drag_start = None;
+                false
+            }
+            Msg::Undo => {
+                if self.brightness != 0 || self.contrast != 0 {
+                    self.brightness = 0;
+                    self.contrast = 0;
+                } else if self.history.len() > 1 {
+                    self.history.pop();
+                    self.revision += 1;
+                }
+                self.selection = None;
+                true
+            }
+            Msg::Revert => {
+                self.history.truncate(1);
+                self.revision += 1;
+                self.brightness = 0;
+                self.contrast = 0;
+                self.selection = None;
+                true
+            }
+            Msg::Download => {
+                if self.download(&ctx.props().title).is_none() {
+                    log::error!("Couldn't download the edited photo");
+                }
+                false
+            }
+        }
+    }
+
+    fn rendered(&mut self, _ctx: &Context<Self>, _first_render: bool) {
+        let canvas = match self.canvas.cast::<HtmlCanvasElement>() {
+            Some(canvas) => canvas,
+            None => return,
+        };
+        let state = (self.revision, self.brightness, self.contrast);
+        if self.drawn == Some(state) {
+            return;
+        }
+        if let Some(image) = self.adjusted() {
+            if draw(&canvas, &image).is_none() {
+                log::error!("Couldn't draw the photo being edited");
+            }
+            self.drawn = Some(state);
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let link = ctx.link();
+
+        if self.failed {
+            return html! {
+                <p>{ "Couldn't open this photo for editing." }</p>
+            };
+        }
+        let image = match self.current() {
+            Some(image) => image,
+            None => {
+                return html! {
+                    <p>{ "Opening photo..." }</p>
+                }
+            }
+        };
+
+        let slider = |id: &str, label: &str, value: i32, msg: fn(i32) -> Msg| {
+            let oninput = link.callback(move |e: InputEvent| {
+                let input: HtmlInputElement = e.target_unchecked_into();
+                msg(input.value().parse().unwrap_or(0))
+            });
+            html! {
+                <div class="field-row">
+                    <label for={id.to_string()}>{ label }</label>
+                    <input
+                        id={id.to_string()}
+                        type="range"
+                        min="-100"
+                        max="100"
+                        value={value.to_string()}
+                        {oninput}
+                    />
+                </div>
+            }
+        };
+
+        // The selection box is drawn over the canvas, scaled along with it.
+        let selection_style = self.selection.map(|r| {
+            format!(
+                "left: {}%; top: {}%; width: {}%; height: {}%;",
+                r.x as f64 / image.width as f64 * 100.0,
+                r.y as f64 / image.height as f64 * 100.0,
+                r.width as f64 / image.width as f64 * 100.0,
+                r.height as f64 / image.height as f64 * 100.0,
+            )
+        });
+
+        html! {
+            <div class="photo-editor">
+                <div class="photo-editor-toolbar">
+                    <button onclick={link.callback(|_| Msg::RotateAnticlockwise)} title="Rotate left">{ "⟲" }</button>
+                    <button onclick={link.callback(|_| Msg::RotateClockwise)} title="Rotate right">{ "⟳" }</button>
+                    <button onclick={link.callback(|_| Msg::FlipHorizontal)} title="Flip horizontally">{ "⇆" }</button>
+                    <button onclick={link.callback(|_| Msg::FlipVertical)} title="Flip vertically">{ "⇅" }</button>
+                    <button
+                        onclick={link.callback(|_| Msg::Crop)}
+                        disabled={self.selection.is_none()}
+                        title="Drag on the photo to select an area first"
+                    >
+                        { "Crop" }
+                    </button>
+                    <button onclick={link.callback(|_| Msg::Greyscale)}>{ "Greyscale" }</button>
+                    <button onclick={link.callback(|_| Msg::Dither)} title="Dither to 256 colours">{ "256 Colours" }</button>
+                </div>
+                <div class="photo-editor-canvas">
+                    <div class="photo-editor-stage">
+                        <canvas
+                            ref={self.canvas.clone()}
+                            onmousedown={link.callback(Msg::SelectStart)}
+                            onmousemove={link.callback(Msg::SelectMove)}
+                            onmouseup={link.callback(|_| Msg::SelectEnd)}
+                            onmouseleave={link.callback(|_| Msg::SelectEnd)}
+                        />
+                        if let Some(style) = selection_style {
+                            <div class="photo-editor-selection" {style}></div>
+                        }
+                    </div>
+                </div>
+                { slider("editor-brightness", "Brightness:", self.brightness, Msg::SetBrightness) }
+                { slider("editor-contrast", "Contrast:", self.contrast, Msg::SetContrast) }
+                <div class="photo-editor-actions">
+                    <button onclick={link.callback(|_| Msg::Undo)}>{ "Undo" }</button>
+                    <button onclick={link.callback(|_| Msg::Revert)}>{ "Revert" }</button>
+                    <button onclick={link.callback(|_| Msg::Download)}>{ "Save as PNG" }</button>
+                    <button onclick={ctx.props().on_close.reform(|_| ())}>{ "Done" }</button>
+                </div>
+                <div class="status-bar">
+                    <p class="status-bar-field">{ format!("{} x {}", image.width, image.height) }</p>
+                    <p class="status-bar-field">
+                        {
+                            match self.selection {
+                                Some(r) => format!("Selection: {} x {}", r.width, r.height),
+                                None => "No selection".to_string(),
+                            }
+                        }
+                    </p>
+                </div>
+            </div>
+        }
+    }
+}
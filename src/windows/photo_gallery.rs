@@ -0,0 +1,71 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use gloo_net::http::Request;
+use serde::Deserialize;
+
+pub const GALLERY_DIR: &str = "assets/photo_gallery";
+const MANIFEST_URL: &str = "assets/photo_gallery/manifest.json";
+
+/// A photo from the gallery manifest, regenerated by `tools/gallery`.
+#[derive(Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct Photo {
+    /// Path relative to the gallery directory.
+    pub file: String,
+    pub title: String,
+    #[serde(default)]
+    pub caption: Option<String>,
+    #[serde(default)]
+    pub date: Option<String>,
+    #[serde(default)]
+    pub camera: Option<String>,
+    #[serde(default)]
+    pub film_stock: Option<String>,
+}
+impl Photo {
+    /// Stable identifier for this photo, used to key its Properties window.
+    pub fn id(&self) -> usize {
+        let mut hasher = DefaultHasher::new();
+        self.file.hash(&mut hasher);
+        hasher.finish() as usize
+    }
+
+    pub fn url(&self) -> String {
+        format!("{}/{}", GALLERY_DIR, self.file)
+    }
+}
+
+/// A folder of photos, possibly containing more folders.
+#[derive(Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct Album {
+    pub name: String,
+    #[serde(default)]
+    pub photos: Vec<Photo>,
+    #[serde(default)]
+    pub albums: Vec<Album>,
+}
+impl Album {
+    /// Follows `path` as child indices from this album.
+    pub fn find(&self, path: &[usize]) -> Option<&Album> {
+        match path.split_first() {
+            Some((first, rest)) => self.albums.get(*first)?.find(rest),
+            None => Some(self),
+        }
+    }
+
+    /// Number of photos in this album and all of its sub-albums.
+    pub fn total_photos(&self) -> usize {
+        self.photos.len() + self.albums.iter().map(Album::total_photos).sum::<usize>()
+    }
+}
+
+pub async fn fetch_manifest() -> Result<Album, String> {
+    let resp = Request::get(MANIFEST_URL)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    if !resp.ok() {
+        return Err(format!("{} {}", resp.status(), resp.status_text()));
+    }
+    resp.json::<Album>().await.map_err(|e| e.to_string())
+}
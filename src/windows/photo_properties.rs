@@ -0,0 +1,186 @@
+use gloo::net::http::Request;
+use wasm_bindgen_futures::spawn_local;
+use yew::{function_component, html, use_effect_with_deps, use_state, Callback, Html, Properties};
+
+use super::photo_gallery::Photo;
+use crate::exif::{self, Exif};
+
+enum PropertiesState {
+    Loading,
+    Loaded { size: usize, exif: Option<Exif> },
+    Error(String),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum PropertiesTab {
+    General,
+    Details,
+}
+
+/// "3.16 MB (3,311,011 bytes)", like Explorer's Properties dialog.
+fn format_size(bytes: usize) -> String {
+    let digits = bytes.to_string();
+    // A comma goes before every digit with a multiple of three after it.
+    let first_group = digits.len() % 3;
+    let mut grouped = String::new();
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && i % 3 == first_group {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+
+    let size = if bytes >= 1024 * 1024 {
+        format!("{:.2} MB", bytes as f64 / (1024.0 * 1024.0))
+    } else {
+        format!("{:.1} KB", bytes as f64 / 1024.0)
+    };
+    format!("{} ({} bytes)", size, grouped)
+}
+
+fn file_type(file: &str) -> String {
+    let extension = file.rsplit('.').next().unwrap_or_default().to_ascii_uppercase();
+    match extension.as_str() {
+        "JPG" | "JPEG" => "JPEG Image".to_string(),
+        other => format!("{} Image", other),
+    }
+}
+
+async fn fetch_bytes(url: &str) -> Result<Vec<u8>, String> {
+    let resp = Request::get(url).send().await.map_err(|e| e.to_string())?;
+    if !resp.ok() {
+        return Err(format!("{} {}", resp.status(), resp.status_text()));
+    }
+    resp.binary().await.map_err(|e| e.to_string())
+}
+
+fn row(label: &str, value: Option<String>) -> Html {
+    match value {
+        Some(value) => html! {
+            <tr>
+                <td>{ format!("{}:", label) }</td>
+                <td>{ value }</td>
+            </tr>
+        },
+        None => html! {},
+    }
+}
+
+fn view_exif(exif: &Exif) -> Html {
+    html! {
+        <table class="photo-properties-table">
+            { row("Camera", exif.camera()) }
+            { row("Lens", exif.lens()) }
+            { row("Exposure", exif.exposure()) }
+            { row("Aperture", exif.aperture()) }
+            { row("ISO", exif.iso.map(|iso| iso.to_string())) }
+            { row("Focal length", exif.focal()) }
+            { row("Date taken", exif.date()) }
+            if let Some(gps) = &exif.gps {
+                <tr>
+                    <td>{ "Location:" }</td>
+                    <td>
+                        <a href={gps.map_url()} target="_blank" rel="noopener noreferrer">
+                            { gps.format() }
+                        </a>
+                    </td>
+                </tr>
+                { row("Altitude", gps.altitude.map(|a| format!("{:.0} m", a))) }
+            }
+        </table>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct PhotoPropertiesProps {
+    pub photo: Photo,
+}
+
+/// A Win98 style Properties dialog for a photo, reading its EXIF tags.
+#[function_component(PhotoProperties)]
+pub fn photo_properties(props: &PhotoPropertiesProps) -> Html {
+    let photo = &props.photo;
+    let state = use_state(|| PropertiesState::Loading);
+    let tab = use_state(|| PropertiesTab::General);
+
+    {
+        let state = state.clone();
+        use_effect_with_deps(
+            move |url: &String| {
+                let url = url.clone();
+                spawn_local(async move {
+                    match fetch_bytes(&url).await {
+                        Ok(bytes) => state.set(PropertiesState::Loaded {
+                            size: bytes.len(),
+                            exif: exif::parse(&bytes).filter(|exif| !exif.is_empty()),
+                        }),
+                        Err(e) => state.set(PropertiesState::Error(e)),
+                    }
+                });
+                || ()
+            },
+            photo.url(),
+        );
+    }
+
+    let tab_button = |target: PropertiesTab, label: &str| {
+        let tab = tab.clone();
+        let selected = *tab == target;
+        html! {
+            <button
+                role="tab"
+                aria-selected={selected.to_string()}
+                onclick={Callback::from(move |_| tab.set(target))}
+            >
+                { label }
+            </button>
+        }
+    };
+
+    let location = match photo.file.rsplit_once('/') {
+        Some((folder, _)) => format!("C:\\home\\roan\\photos\\{}", folder.replace('/', "\\")),
+        None => "C:\\home\\roan\\photos".to_string(),
+    };
+    let file_name = photo.file.rsplit('/').next().unwrap_or(&photo.file).to_string();
+
+    let body = match (&*state, *tab) {
+        (PropertiesState::Loading, _) => html! {
+            <p>{ "Reading file..." }</p>
+        },
+        (PropertiesState::Error(error), _) => html! {
+            <p>{ format!("Couldn't read this photo: {}", error) }</p>
+        },
+        (PropertiesState::Loaded { size, .. }, PropertiesTab::General) => html! {
+            <table class="photo-properties-table">
+                { row("Type", Some(file_type(&photo.file))) }
+                { row("Location", Some(location)) }
+                { row("Size", Some(format_size(*size))) }
+                { row("Camera", photo.camera.clone()) }
+                { row("Film", photo.film_stock.clone()) }
+                { row("Date", photo.date.clone()) }
+            </table>
+        },
+        (PropertiesState::Loaded { exif: Some(exif), .. }, PropertiesTab::Details) => view_exif(exif),
+        (PropertiesState::Loaded { exif: None, .. }, PropertiesTab::Details) => html! {
+            <p>{ "This photo has no EXIF information." }</p>
+        },
+    };
+
+    html! {
+        <div class="photo-properties">
+            <div class="photo-properties-header">
+                <img src="assets/icons/kodak_imaging-0.png" alt="" />
+                <span>{ file_name }</span>
+            </div>
+            <menu role="tablist" class="tabs">
+                { tab_button(PropertiesTab::General, "General") }
+                { tab_button(PropertiesTab::Details, "Details") }
+            </menu>
+            <div class="window" role="tabpanel">
+                <div class="window-body">
+                    { body }
+                </div>
+            </div>
+        </div>
+    }
+}
@@ -1,76 +1,411 @@
-use yew::{function_component, html, use_state, Callback, Html};
+use std::collections::HashSet;
+use std::rc::Rc;
 
-#[function_component(PhotoViewer)]
-pub fn photo_viewer() -> Html {
-    let images = vec![
-        "000005220003.jpg",
-        "000005220005.jpg",
-        "000006710020.jpg",
-        "000006710032.jpg",
-        "000101080016.png",
-        "000101080027.png",
-        "000101080035.png",
-        "000156840002.jpg",
-        "000156840009.jpg",
-        "A028792-R1-19-18A.JPG",
-        "cloud.jpg",
-        "PUNCH.png",
-    ];
+use futures::join;
+use gloo::timers::callback::Timeout;
+use wasm_bindgen_futures::spawn_local;
+use web_sys::{HtmlImageElement, HtmlSelectElement, KeyboardEvent};
+use yew::{
+    classes, function_component, html, use_effect_with_deps, use_reducer, use_state, Callback,
+    Html, Properties, Reducible, TargetCast,
+};
+
+use super::photo_editor::PhotoEditor;
+use super::photo_gallery::{fetch_manifest, Album, Photo, GALLERY_DIR};
+use super::photo_zoom::ZoomableImage;
+use super::responsive_image::{fetch_variants, ResponsiveImage, Variants};
+
+/// Seconds between photos that the slideshow can be set to.
+const SLIDESHOW_INTERVALS: [u32; 4] = [3, 5, 10, 30];
+
+enum GalleryState {
+    Loading,
+    Loaded(Album),
+    Error(String),
+}
+
+/// Files listed in the manifest that failed to load.
+#[derive(Default)]
+struct MissingFiles(HashSet<String>);
+impl Reducible for MissingFiles {
+    type Action = String;
+
+    fn reduce(self: Rc<Self>, file: String) -> Rc<Self> {
+        if self.0.contains(&file) {
+            return self;
+        }
+        let mut missing = self.0.clone();
+        missing.insert(file);
+        MissingFiles(missing).into()
+    }
+}
+
+fn view_album_tree(
+    album: &Album,
+    path: Vec<usize>,
+    current: &[usize],
+    on_select: &Callback<Vec<usize>>,
+) -> Html {
+    let onclick = {
+        let on_select = on_select.clone();
+        let path = path.clone();
+        Callback::from(move |e: yew::MouseEvent| {
+            e.prevent_default();
+            on_select.emit(path.clone())
+        })
+    };
+    let is_current = path == current;
+
+    html! {
+        <li>
+            <a href="#" class={classes!(is_current.then_some("selected"))} {onclick}>
+                <img src="assets/icons/directory_closed-4.png" alt="" />
+                { format!("{} ({})", album.name, album.total_photos()) }
+            </a>
+            if !album.albums.is_empty() {
+                <ul>
+                    {
+                        album.albums.iter().enumerate().map(|(i, child)| {
+                            let mut child_path = path.clone();
+                            child_path.push(i);
+                            view_album_tree(child, child_path, current, on_select)
+                        }).collect::<Html>()
+                    }
+                </ul>
+            }
+        </li>
+    }
+}
+
+fn view_details(photo: &Photo) -> Html {
+    let details = [&photo.date, &photo.camera, &photo.film_stock]
+        .into_iter()
+        .flatten()
+        .cloned()
+        .collect::<Vec<_>>();
 
+    html! {
+        <div class="photo-details">
+            <b>{ photo.title.clone() }</b>
+            if let Some(caption) = &photo.caption {
+                <p>{ caption.clone() }</p>
+            }
+            if !details.is_empty() {
+                <p class="photo-meta">{ details.join(" · ") }</p>
+            }
+        </div>
+    }
+}
+
+/// Starts fetching the photos either side of the current one so flicking
+/// through doesn't wait on the network.
+fn preload(photos: &[Photo], current: usize) {
+    if photos.len() < 2 {
+        return;
+    }
+    let next = (current + 1) % photos.len();
+    let previous = (current + photos.len() - 1) % photos.len();
+    for photo in [&photos[next], &photos[previous]] {
+        if let Ok(image) = HtmlImageElement::new() {
+            image.set_src(&photo.url());
+        }
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct PhotoViewerProps {
+    pub open_properties: Callback<Photo>,
+}
+
+#[function_component(PhotoViewer)]
+pub fn photo_viewer(props: &PhotoViewerProps) -> Html {
+    let state = use_state(|| GalleryState::Loading);
+    let variants = use_state(Variants::new);
+    let album_path = use_state(Vec::<usize>::new);
     let photo_id = use_state(|| 0);
-    let decrement = {
+    let missing = use_reducer(MissingFiles::default);
+    let playing = use_state(|| false);
+    let slideshow_interval = use_state(|| SLIDESHOW_INTERVALS[1]);
+    let editing = use_state(|| false);
+
+    {
+        let state = state.clone();
+        let variants = variants.clone();
+        use_effect_with_deps(
+            move |_| {
+                spawn_local(async move {
+                    // Both before showing anything, so the first photo doesn't
+                    // load at full size and then again as a variant.
+                    let (album, found) = join!(fetch_manifest(), fetch_variants(GALLERY_DIR));
+                    variants.set(found);
+                    match album {
+                        Ok(album) => state.set(GalleryState::Loaded(album)),
+                        Err(e) => state.set(GalleryState::Error(e)),
+                    }
+                });
+                || ()
+            },
+            (),
+        );
+    }
+
+    let images = match &*state {
+        GalleryState::Loaded(root) => root.find(&album_path).unwrap_or(root).photos.clone(),
+        _ => Vec::new(),
+    };
+
+    // Moves by `offset` photos, wrapping around the album.
+    let go = {
         let photo_id = photo_id.clone();
-        let new_photo_id = if *photo_id == 0 {
-            images.len() - 1
-        } else {
-            *photo_id - 1
-        };
-        Callback::from(move |_| photo_id.set(new_photo_id))
+        let len = images.len() as isize;
+        Callback::from(move |offset: isize| {
+            if len > 0 {
+                photo_id.set((*photo_id as isize + offset).rem_euclid(len) as usize);
+            }
+        })
     };
-    let increment = {
+
+    {
+        let images = images.clone();
+        let count = images.len();
+        use_effect_with_deps(
+            move |(photo_id, _, _)| {
+                preload(&images, *photo_id);
+                || ()
+            },
+            // The image count changes when the manifest arrives.
+            (*photo_id, (*album_path).clone(), count),
+        );
+    }
+
+    // Restarted whenever the photo changes, so skipping by hand resets the timer.
+    {
+        let go = go.clone();
+        use_effect_with_deps(
+            move |(playing, interval, _, _)| {
+                let timeout = playing.then(|| Timeout::new(interval * 1000, move || go.emit(1)));
+                move || drop(timeout)
+            },
+            (*playing, *slideshow_interval, *photo_id, images.len()),
+        );
+    }
+
+    let root = match &*state {
+        GalleryState::Loaded(root) => root,
+        GalleryState::Loading => {
+            return html! {
+                <p>{ "Loading photos..." }</p>
+            }
+        }
+        GalleryState::Error(error) => {
+            return html! {
+                <p>{ format!("Couldn't load the photo gallery: {}", error) }</p>
+            }
+        }
+    };
+
+    let on_select_album = {
+        let album_path = album_path.clone();
         let photo_id = photo_id.clone();
-        let new_photo_id = if *photo_id == images.len() - 1 {
-            0
-        } else {
-            *photo_id + 1
-        };
-        Callback::from(move |_| photo_id.set(new_photo_id))
+        let playing = playing.clone();
+        let editing = editing.clone();
+        Callback::from(move |path: Vec<usize>| {
+            photo_id.set(0);
+            playing.set(false);
+            editing.set(false);
+            album_path.set(path);
+        })
+    };
+    let on_missing = |file: &str| {
+        let missing = missing.clone();
+        let file = file.to_string();
+        Callback::from(move |_| missing.dispatch(file.clone()))
     };
 
-    let photo = images[*photo_id];
+    let tree = html! {
+        <ul class="tree-view photo-albums">
+            { view_album_tree(root, Vec::new(), &album_path, &on_select_album) }
+        </ul>
+    };
 
-    html! {
-        <div class="pp">
-        <div class="photo-window">
-            <div class="photo-path-container">
-                // current photo
-                <div class="photo-path">
-                <span>{format!("C:/home/roan/photos/{}", photo)}</span><br/>
-                <img src={format!("assets/photo_gallery/{}", photo)}/>
+    let photo = match images.get(*photo_id) {
+        Some(photo) => photo,
+        None => {
+            return html! {
+                <div class="pp">
+                <div class="photo-browser">
+                    { tree }
+                    <p>{ "There are no photos in this folder." }</p>
                 </div>
-            </div>
-            <div class="photo-controls">
-                <button onclick={decrement}>{" < "}</button>
-                <button onclick={increment}>{" > "}</button>
-            </div>
-            <div>
-            <div class="photo-selector-container">
-                <div class="photo-selector">
-                    // photos
-                    {
-                        images.into_iter().enumerate().map(|(i, image)| html! {
-                            <img
-                                class={(i==*photo_id).then_some("selected")}
-                                onclick={{
-                                    let photo_id = photo_id.clone();
-                                    Callback::from(move |_| photo_id.set(i))
-                                }}
-                                src={format!("assets/photo_gallery/{}", image)}
-                            />
-                        }).collect::<Html>()
+                </div>
+            }
+        }
+    };
+
+    let decrement = {
+        let go = go.clone();
+        Callback::from(move |_| go.emit(-1))
+    };
+    let increment = {
+        let go = go.clone();
+        Callback::from(move |_| go.emit(1))
+    };
+    let toggle_playing = {
+        let playing = playing.clone();
+        Callback::from(move |_| playing.set(!*playing))
+    };
+    let on_interval = {
+        let slideshow_interval = slideshow_interval.clone();
+        Callback::from(move |e: yew::Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            if let Ok(seconds) = select.value().parse() {
+                slideshow_interval.set(seconds);
+            }
+        })
+    };
+    let set_editing = {
+        let editing = editing.clone();
+        let playing = playing.clone();
+        Callback::from(move |value: bool| {
+            playing.set(false);
+            editing.set(value);
+        })
+    };
+    let onkeydown = {
+        let go = go.clone();
+        let playing = playing.clone();
+        let editing = *editing;
+        Callback::from(move |e: KeyboardEvent| {
+            if editing {
+                return;
+            }
+            // Leave typing in the interval picker alone.
+            if e.target_unchecked_into::<web_sys::Element>().tag_name() == "SELECT" {
+                return;
+            }
+            match e.key().as_str() {
+                "ArrowLeft" => go.emit(-1),
+                "ArrowRight" => go.emit(1),
+                " " => playing.set(!*playing),
+                "Escape" => playing.set(false),
+                _ => return,
+            }
+            e.prevent_default();
+        })
+    };
+
+    let open_properties = {
+        let photo = photo.clone();
+        let open_properties = props.open_properties.clone();
+        Callback::from(move |_| open_properties.emit(photo.clone()))
+    };
+    let oncontextmenu = {
+        let open_properties = open_properties.clone();
+        Callback::from(move |e: yew::MouseEvent| {
+            e.prevent_default();
+            open_properties.emit(e);
+        })
+    };
+
+    let is_missing = missing.0.contains(&photo.file);
+
+    html! {
+        <div class="pp" tabindex="0" {onkeydown}>
+        <div class="photo-browser">
+            { tree }
+            <div class="photo-window">
+                <div class="photo-path-container">
+                    // current photo
+                    <div class="photo-path" {oncontextmenu}>
+                    <span>{format!("C:/home/roan/photos/{}", photo.file)}</span><br/>
+                    if *editing {
+                        <PhotoEditor
+                            key={photo.file.clone()}
+                            src={photo.url()}
+                            title={photo.title.clone()}
+                            on_close={set_editing.reform(|_| false)}
+                        />
+                    } else if is_missing {
+                        <div class="photo-missing">
+                            <img src="assets/icons/msg_error-0.png" alt="" />
+                            <span>{ "This photo couldn't be found." }</span>
+                        </div>
+                    } else {
+                        <ZoomableImage
+                            src={photo.url()}
+                            alt={photo.title.clone()}
+                            onerror={on_missing(&photo.file)}
+                            on_swipe={go.clone()}
+                        />
                     }
+                    </div>
+                </div>
+                { view_details(photo) }
+                <div class="photo-controls">
+                    <button onclick={decrement} aria-label="Previous photo">{" < "}</button>
+                    <button
+                        class="slideshow-button"
+                        onclick={toggle_playing}
+                        aria-label={if *playing { "Pause slideshow" } else { "Play slideshow" }}
+                        aria-pressed={playing.to_string()}
+                    >
+                        { if *playing { "❚❚" } else { "▶" } }
+                    </button>
+                    <button onclick={increment} aria-label="Next photo">{" > "}</button>
+                    <button onclick={open_properties}>{ "Properties" }</button>
+                    <button onclick={set_editing.reform(|_| true)} disabled={*editing || is_missing}>{ "Edit" }</button>
+                    <div class="field-row">
+                        <label for="slideshow-interval">{ "Every" }</label>
+                        <select id="slideshow-interval" onchange={on_interval}>
+                            {
+                                SLIDESHOW_INTERVALS.iter().map(|seconds| html! {
+                                    <option
+                                        value={seconds.to_string()}
+                                        selected={*seconds == *slideshow_interval}
+                                    >
+                                        { format!("{} seconds", seconds) }
+                                    </option>
+                                }).collect::<Html>()
+                            }
+                        </select>
+                    </div>
+                </div>
+                <div>
+                <div class="photo-selector-container">
+                    <div class="photo-selector">
+                        // photos
+                        {
+                            images.iter().enumerate().map(|(i, image)| {
+                                let image_missing = missing.0.contains(&image.file);
+                                html! {
+                                    <ResponsiveImage
+                                        key={image.file.clone()}
+                                        dir={GALLERY_DIR}
+                                        file={image.file.clone()}
+                                        info={variants.get(&image.file).cloned()}
+                                        sizes="160px"
+                                        alt={image.title.clone()}
+                                        class={classes!(
+                                            (i == *photo_id).then_some("selected"),
+                                            image_missing.then_some("missing"),
+                                        )}
+                                        title={if image_missing {
+                                            format!("{} (missing)", image.title)
+                                        } else {
+                                            image.title.clone()
+                                        }}
+                                        onclick={{
+                                            let photo_id = photo_id.clone();
+                                            Callback::from(move |_| photo_id.set(i))
+                                        }}
+                                        onerror={on_missing(&image.file)}
+                                    />
+                                }
+                            }).collect::<Html>()
+                        }
+                    </div>
+                </div>
                 </div>
-            </div>
             </div>
         </div>
         </div>
@@ -0,0 +1,425 @@
+use gloo::events::{EventListener, EventListenerOptions};
+use gloo::utils::{document, window as browser_window};
+use wasm_bindgen::JsCast;
+use web_sys::{Element, HtmlImageElement, WheelEvent};
+use yew::events::{MouseEvent, TouchEvent};
+use yew::prelude::*;
+
+use crate::copland::MoveEvent;
+
+const MIN_ZOOM: f64 = 0.05;
+const MAX_ZOOM: f64 = 8.0;
+const WHEEL_STEP: f64 = 1.1;
+/// How far a finger has to travel sideways to count as a swipe.
+const SWIPE_DISTANCE: i32 = 50;
+
+pub enum Msg {
+    Loaded,
+    Wheel(f64, f64, f64),
+    DragStart(MoveEvent),
+    DragMove(MoveEvent),
+    DragEnd,
+    ToggleFit(MouseEvent),
+    ToggleFullscreen,
+    FullscreenChanged,
+}
+
+#[derive(Properties, PartialEq)]
+pub struct ZoomableImageProps {
+    pub src: String,
+    #[prop_or_default]
+    pub alt: String,
+    #[prop_or_default]
+    pub onerror: Callback<Event>,
+    /// Called with -1 or 1 when the unzoomed image is swiped right or left.
+    #[prop_or_default]
+    pub on_swipe: Callback<isize>,
+}
+
+/// Where a drag or pinch started, so moves are relative to it.
+#[derive(Clone, Copy)]
+enum Gesture {
+    Pan {
+        start_x: i32,
+        start_y: i32,
+        last: (i32, i32),
+        start_pan: (f64, f64),
+        touch: bool,
+    },
+    Pinch {
+        start_distance: f64,
+        start_zoom: f64,
+    },
+}
+
+/// An image that can be zoomed with the wheel or a pinch, panned by dragging
+/// and shown fullscreen.
+pub struct ZoomableImage {
+    src: String,
+    container: NodeRef,
+    image: NodeRef,
+    natural_size: (f64, f64),
+    zoom: f64,
+    pan: (f64, f64),
+    /// Whether the zoom tracks the container size rather than being user-set.
+    fit: bool,
+    fullscreen: bool,
+    gesture: Option<Gesture>,
+    wheel_listener: Option<EventListener>,
+    fullscreen_listener: Option<EventListener>,
+    mouse_move_listener: Option<EventListener>,
+    mouse_up_listener: Option<EventListener>,
+    touch_move_listener: Option<EventListener>,
+    touch_up_listener: Option<EventListener>,
+}
+
+impl ZoomableImage {
+    fn container_size(&self) -> (f64, f64) {
+        self.container
+            .cast::<Element>()
+            .map_or((0.0, 0.0), |c| (c.client_width() as f64, c.client_height() as f64))
+    }
+
+    /// Zoom that fits the whole image in the container without upscaling.
+    fn fit_zoom(&self) -> f64 {
+        let (width, height) = self.container_size();
+        let (natural_width, natural_height) = self.natural_size;
+        if natural_width == 0.0 || natural_height == 0.0 {
+            return 1.0;
+        }
+        (width / natural_width).min(height / natural_height).min(1.0)
+    }
+
+    fn reset_to_fit(&mut self) {
+        self.zoom = self.fit_zoom();
+        self.pan = (0.0, 0.0);
+        self.fit = true;
+    }
+
+    /// Keeps the image from being dragged out of view.
+    fn clamp_pan(&mut self) {
+        let (width, height) = self.container_size();
+        let max_x = ((self.natural_size.0 * self.zoom - width) / 2.0).max(0.0);
+        let max_y = ((self.natural_size.1 * self.zoom - height) / 2.0).max(0.0);
+        self.pan = (
+            self.pan.0.clamp(-max_x, max_x),
+            self.pan.1.clamp(-max_y, max_y),
+        );
+    }
+
+    /// Zooms by `factor` keeping the point at (`x`, `y`), relative to the
+    /// container's centre, in place.
+    fn zoom_at(&mut self, factor: f64, x: f64, y: f64) {
+        let zoom = (self.zoom * factor).clamp(MIN_ZOOM, MAX_ZOOM);
+        let factor = zoom / self.zoom;
+        self.pan = (
+            (self.pan.0 - x) * factor + x,
+            (self.pan.1 - y) * factor + y,
+        );
+        self.zoom = zoom;
+        self.fit = false;
+        self.clamp_pan();
+    }
+
+    /// Converts client coordinates to ones relative to the container's centre.
+    fn offset_from_centre(&self, client_x: f64, client_y: f64) -> (f64, f64) {
+        match self.container.cast::<Element>() {
+            Some(container) => {
+                let rect = container.get_bounding_client_rect();
+                (
+                    client_x - rect.left() - rect.width() / 2.0,
+                    client_y - rect.top() - rect.height() / 2.0,
+                )
+            }
+            None => (0.0, 0.0),
+        }
+    }
+
+    fn listen_for_drag(&mut self, ctx: &Context<Self>) {
+        let on_mouse_move = ctx.link().callback(Msg::DragMove);
+        self.mouse_move_listener = Some(EventListener::new(
+            &browser_window(),
+            "mousemove",
+            move |e| {
+                let event = e.dyn_ref::<MouseEvent>().unwrap();
+                on_mouse_move.emit(MoveEvent::MouseEvent(event.clone()));
+            },
+        ));
+
+        let on_mouse_up = ctx.link().callback(|_| Msg::DragEnd);
+        self.mouse_up_listener = Some(EventListener::new(
+            &browser_window(),
+            "mouseup",
+            move |e| on_mouse_up.emit(e.clone()),
+        ));
+
+        let on_touch_move = ctx.link().callback(Msg::DragMove);
+        self.touch_move_listener = Some(EventListener::new(
+            &browser_window(),
+            "touchmove",
+            move |e| {
+                let event = e.dyn_ref::<TouchEvent>().unwrap();
+                on_touch_move.emit(MoveEvent::TouchEvent(event.clone()));
+            },
+        ));
+
+        let on_touch_up = ctx.link().callback(|_| Msg::DragEnd);
+        self.touch_up_listener = Some(EventListener::new(
+            &browser_window(),
+            "touchend",
+            move |e| on_touch_up.emit(e.clone()),
+        ));
+    }
+}
+
+impl Component for ZoomableImage {
+    type Message = Msg;
+    type Properties = ZoomableImageProps;
+
+    fn create(ctx: &Context<Self>) -> Self {
+        Self {
+            src: ctx.props().src.clone(),
+            container: NodeRef::default(),
+            image: NodeRef::default(),
+            natural_size: (0.0, 0.0),
+            zoom: 1.0,
+            pan: (0.0, 0.0),
+            fit: true,
+            fullscreen: false,
+            gesture: None,
+            wheel_listener: None,
+            fullscreen_listener: None,
+            mouse_move_listener: None,
+            mouse_up_listener: None,
+            touch_move_listener: None,
+            touch_up_listener: None,
+        }
+    }
+
+    fn changed(&mut self, ctx: &Context<Self>) -> bool {
+        // A new photo is fitted again once it has loaded.
+        if ctx.props().src != self.src {
+            self.src = ctx.props().src.clone();
+            self.natural_size = (0.0, 0.0);
+            self.gesture = None;
+            self.reset_to_fit();
+        }
+        true
+    }
+
+    fn rendered(&mut self, ctx: &Context<Self>, first_render: bool) {
+        if !first_render {
+            return;
+        }
+
+        // Registered by hand so it isn't passive and can stop the page scrolling.
+        if let Some(container) = self.container.cast::<Element>() {
+            let on_wheel = ctx.link().callback(|(x, y, delta)| Msg::Wheel(x, y, delta));
+            let options = EventListenerOptions::enable_prevent_default();
+            self.wheel_listener = Some(EventListener::new_with_options(
+                &container,
+                "wheel",
+                options,
+                move |e| {
+                    let event = e.dyn_ref::<WheelEvent>().unwrap();
+                    event.prevent_default();
+                    on_wheel.emit((
+                        event.client_x() as f64,
+                        event.client_y() as f64,
+                        event.delta_y(),
+                    ));
+                },
+            ));
+        }
+
+        let on_fullscreen = ctx.link().callback(|_| Msg::FullscreenChanged);
+        self.fullscreen_listener = Some(EventListener::new(
+            &document(),
+            "fullscreenchange",
+            move |e| on_fullscreen.emit(e.clone()),
+        ));
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Msg::Loaded => {
+                if let Some(image) = self.image.cast::<HtmlImageElement>() {
+                    self.natural_size = (image.natural_width() as f64, image.natural_height() as f64);
+                }
+                self.reset_to_fit();
+                true
+            }
+            Msg::Wheel(client_x, client_y, delta) => {
+                let factor = if delta < 0.0 { WHEEL_STEP } else { 1.0 / WHEEL_STEP };
+                let (x, y) = self.offset_from_centre(client_x, client_y);
+                self.zoom_at(factor, x, y);
+                true
+            }
+            Msg::DragStart(e) => {
+                e.prevent_default();
+                self.gesture = Some(match e.touch_distance() {
+                    Some(start_distance) => Gesture::Pinch {
+                        start_distance,
+                        start_zoom: self.zoom,
+                    },
+                    None => Gesture::Pan {
+                        start_x: e.client_x(),
+                        start_y: e.client_y(),
+                        last: (e.client_x(), e.client_y()),
+                        start_pan: self.pan,
+                        touch: matches!(e, MoveEvent::TouchEvent(_)),
+                    },
+                });
+                self.listen_for_drag(ctx);
+                true
+            }
+            Msg::DragMove(e) => {
+                // A second finger turns a pan into a pinch part way through.
+                if let (Some(Gesture::Pan { .. }), Some(start_distance)) =
+                    (&self.gesture, e.touch_distance())
+                {
+                    self.gesture = Some(Gesture::Pinch {
+                        start_distance,
+                        start_zoom: self.zoom,
+                    });
+                }
+
+                match self.gesture {
+                    Some(Gesture::Pan {
+                        start_x,
+                        start_y,
+                        start_pan,
+                        touch,
+                        ..
+                    }) => {
+                        self.gesture = Some(Gesture::Pan {
+                            start_x,
+                            start_y,
+                            last: (e.client_x(), e.client_y()),
+                            start_pan,
+                            touch,
+                        });
+                        self.pan = (
+                            start_pan.0 + (e.client_x() - start_x) as f64,
+                            start_pan.1 + (e.client_y() - start_y) as f64,
+                        );
+                        self.clamp_pan();
+                        true
+                    }
+                    Some(Gesture::Pinch {
+                        start_distance,
+                        start_zoom,
+                    }) => match e.touch_distance() {
+                        Some(distance) if start_distance > 0.0 => {
+                            let factor = start_zoom * distance / start_distance / self.zoom;
+                            self.zoom_at(factor, 0.0, 0.0);
+                            true
+                        }
+                        _ => false,
+                    },
+                    None => false,
+                }
+            }
+            Msg::DragEnd => {
+                if let Some(Gesture::Pan {
+                    start_x,
+                    start_y,
+                    last: (x, y),
+                    touch: true,
+                    ..
+                }) = self.gesture
+                {
+                    let (dx, dy) = (x - start_x, y - start_y);
+                    let zoomed_in = !self.fit && self.zoom > self.fit_zoom();
+                    if !zoomed_in && dx.abs() > SWIPE_DISTANCE && dx.abs() > dy.abs() * 2 {
+                        ctx.props().on_swipe.emit(if dx < 0 { 1 } else { -1 });
+                    }
+                }
+                self.gesture = None;
+                self.mouse_move_listener = None;
+                self.mouse_up_listener = None;
+                self.touch_move_listener = None;
+                self.touch_up_listener = None;
+                false
+            }
+            Msg::ToggleFit(e) => {
+                if self.fit {
+                    let (x, y) = self.offset_from_centre(e.client_x() as f64, e.client_y() as f64);
+                    self.zoom_at(1.0 / self.zoom, x, y);
+                } else {
+                    self.reset_to_fit();
+                }
+                true
+            }
+            Msg::ToggleFullscreen => {
+                if document().fullscreen_element().is_some() {
+                    document().exit_fullscreen();
+                } else if let Some(container) = self.container.cast::<Element>() {
+                    let parent = container.parent_element().unwrap_or(container);
+                    if let Err(e) = parent.request_fullscreen() {
+                        log::error!("Couldn't go fullscreen: {:?}", e);
+                    }
+                }
+                false
+            }
+            Msg::FullscreenChanged => {
+                self.fullscreen = document().fullscreen_element().is_some();
+                if self.fit {
+                    self.reset_to_fit();
+                } else {
+                    self.clamp_pan();
+                }
+                true
+            }
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let props = ctx.props();
+        let link = ctx.link();
+
+        let style = format!(
+            "width: {}px; height: {}px; transform: translate(-50%, -50%) translate({}px, {}px);",
+            self.natural_size.0 * self.zoom,
+            self.natural_size.1 * self.zoom,
+            self.pan.0,
+            self.pan.1,
+        );
+        let zoomed_in = !self.fit && self.zoom > self.fit_zoom();
+
+        html! {
+            <div class={classes!("zoomable-image", self.fullscreen.then_some("fullscreen"))}>
+                <div
+                    class={classes!(
+                        "zoomable-image-area",
+                        zoomed_in.then_some("zoomed"),
+                        self.gesture.is_some().then_some("dragging"),
+                    )}
+                    ref={self.container.clone()}
+                    onmousedown={link.callback(|e| Msg::DragStart(MoveEvent::MouseEvent(e)))}
+                    ontouchstart={link.callback(|e| Msg::DragStart(MoveEvent::TouchEvent(e)))}
+                    ondblclick={link.callback(Msg::ToggleFit)}
+                >
+                    <img
+                        ref={self.image.clone()}
+                        src={props.src.clone()}
+                        alt={props.alt.clone()}
+                        {style}
+                        draggable="false"
+                        onload={link.callback(|_| Msg::Loaded)}
+                        onerror={props.onerror.clone()}
+                    />
+                </div>
+                <div class="status-bar">
+                    <p class="status-bar-field">{ format!("{:.0}%", self.zoom * 100.0) }</p>
+                    <p class="status-bar-field">
+                        { format!("{} x {}", self.natural_size.0, self.natural_size.1) }
+                    </p>
+                    <button onclick={link.callback(|_| Msg::ToggleFullscreen)}>
+                        { if self.fullscreen { "Exit Fullscreen" } else { "Fullscreen" } }
+                    </button>
+                </div>
+            </div>
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use wasm_bindgen_futures::spawn_local;
+use yew::{function_component, html, use_effect_with_deps, use_state, Properties};
+
+use super::projects::ProjectData;
+use crate::github::GitHub;
+use crate::markdown::{self, Base};
+
+enum ReadmeState {
+    Loading,
+    Loaded(String),
+    /// GitHub couldn't be reached and nothing was cached.
+    Offline(String),
+}
+
+#[derive(Properties, PartialEq)]
+pub struct ProjectReadmeProps {
+    pub project: ProjectData,
+    #[prop_or_default]
+    pub github: GitHub,
+}
+
+/// A project's README from GitHub in a WordPad style window. The bundled
+/// description stands in when GitHub can't be reached.
+#[function_component(ProjectReadme)]
+pub fn project_readme(props: &ProjectReadmeProps) -> Html {
+    let repo = props.project.github_repo();
+    let state = use_state(|| ReadmeState::Loading);
+
+    {
+        let state = state.clone();
+        let github = props.github.clone();
+        use_effect_with_deps(
+            move |repo: &Option<String>| {
+                match repo.clone() {
+                    Some(repo) => {
+                        let request = github.0.readme(&repo);
+                        spawn_local(async move {
+                            match request.await {
+                                Ok(readme) => state.set(ReadmeState::Loaded(readme)),
+                                Err(e) => state.set(ReadmeState::Offline(e)),
+                            }
+                        });
+                    }
+                    None => state.set(ReadmeState::Offline("no repository".to_string())),
+                }
+                || ()
+            },
+            repo.clone(),
+        );
+    }
+
+    let (page, status) = match (&*state, &repo) {
+        (ReadmeState::Loaded(readme), Some(repo)) => {
+            let base = Base {
+                links: format!("https://github.com/{}/blob/HEAD", repo),
+                images: format!("https://raw.githubusercontent.com/{}/HEAD", repo),
+            };
+            (
+                markdown::render_relative_to(readme, &base),
+                format!("README from github.com/{}", repo),
+            )
+        }
+        (ReadmeState::Offline(error), _) => (
+            markdown::render(&props.project.description),
+            format!("Couldn't load the README ({}), showing a summary", error),
+        ),
+        _ => (html! {}, "Fetching README from GitHub...".to_string()),
+    };
+
+    html! {
+        <div class="readme">
+            if let Some(repo) = &repo {
+                <div class="readme-toolbar">
+                    <a
+                        href={format!("https://github.com/{}", repo)}
+                        target="_blank"
+                        rel="noopener noreferrer"
+                    >
+                        { "View on GitHub" }
+                    </a>
+                </div>
+            }
+            <div class="readme-page">
+                { page }
+            </div>
+            <div class="status-bar">
+                <p class="status-bar-field">{ status }</p>
+            </div>
+        </div>
+    }
+}
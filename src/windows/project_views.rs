@@ -0,0 +1,180 @@
+use std::collections::HashMap;
+
+use yew::{function_component, html, Callback, Html, MouseEvent, Properties};
+
+use super::projects::{ProjectData, SPLASHES_DIR};
+use super::responsive_image::{ResponsiveImage, Variants};
+use crate::github::RepoStats;
+use crate::time_format::RelativeTime;
+
+/// How the explorer lays out matching projects.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ProjectLayout {
+    Icons,
+    Details,
+}
+impl ProjectLayout {
+    pub const ALL: [ProjectLayout; 2] = [ProjectLayout::Icons, ProjectLayout::Details];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ProjectLayout::Icons => "Icons",
+            ProjectLayout::Details => "Details",
+        }
+    }
+}
+
+/// Every tag and technology used by `projects`, once each, alphabetically.
+pub fn all_facets(projects: &[ProjectData]) -> Vec<String> {
+    let mut facets: Vec<String> = vec![];
+    for facet in projects.iter().flat_map(|p| p.facets()) {
+        if !facets.iter().any(|f| f.eq_ignore_ascii_case(facet)) {
+            facets.push(facet.clone());
+        }
+    }
+    facets.sort_by_key(|f| f.to_lowercase());
+    facets
+}
+
+#[derive(Properties, PartialEq)]
+pub struct TagFilterProps {
+    pub facets: Vec<String>,
+    pub selected: Vec<String>,
+    pub on_toggle: Callback<String>,
+}
+
+/// Toggle buttons narrowing the explorer to projects with all chosen tags.
+#[function_component(TagFilter)]
+pub fn tag_filter(props: &TagFilterProps) -> Html {
+    html! {
+        <div class="project-tag-filter" role="group" aria-label="Filter by tag">
+            {
+                props.facets.iter().map(|facet| {
+                    let pressed = props.selected.contains(facet);
+                    let on_toggle = props.on_toggle.clone();
+                    let value = facet.clone();
+                    html! {
+                        <button
+                            aria-pressed={pressed.to_string()}
+                            onclick={Callback::from(move |_| on_toggle.emit(value.clone()))}
+                        >
+                            { facet }
+                        </button>
+                    }
+                }).collect::<Html>()
+            }
+        </div>
+    }
+}
+
+/// Stars and last commit from GitHub, or blanks until they've loaded.
+fn stats_cells(stats: Option<&RepoStats>) -> Html {
+    match stats {
+        Some(stats) => html! {
+            <>
+                <td>{ stats.language.clone().unwrap_or_default() }</td>
+                <td>{ stats.stars }</td>
+                <td>
+                    <RelativeTime timestamp={stats.pushed_timestamp()} />
+                </td>
+            </>
+        },
+        None => html! {
+            <>
+                <td></td>
+                <td></td>
+                <td></td>
+            </>
+        },
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct ProjectListProps {
+    /// Indices into `projects` of the ones to show.
+    pub matching: Vec<usize>,
+    pub projects: Vec<ProjectData>,
+    pub layout: ProjectLayout,
+    pub stats: HashMap<String, RepoStats>,
+    pub splashes: Variants,
+    pub open_project: Callback<usize>,
+}
+
+/// The projects matching the current search in the chosen layout.
+#[function_component(ProjectList)]
+pub fn project_list(props: &ProjectListProps) -> Html {
+    if props.matching.is_empty() {
+        return html! {
+            <p class="project-list-empty">{ "No projects match your search." }</p>
+        };
+    }
+
+    let open = |id: usize| {
+        let open_project = props.open_project.clone();
+        Callback::from(move |e: MouseEvent| {
+            e.prevent_default();
+            open_project.emit(id);
+        })
+    };
+    let stats = |project: &ProjectData| project.github_repo().and_then(|r| props.stats.get(&r));
+
+    match props.layout {
+        ProjectLayout::Icons => html! {
+            <ul class="project-grid">
+                {
+                    props.matching.iter().map(|&id| {
+                        let project = &props.projects[id];
+                        html! {
+                            <li>
+                                <a href="#" onclick={open(id)} title={project.title.clone()}>
+                                    if let Some(splash) = &project.splash {
+                                        <ResponsiveImage
+                                            dir={SPLASHES_DIR}
+                                            file={splash.clone()}
+                                            info={props.splashes.get(splash).cloned()}
+                                            class="project-thumb"
+                                            sizes="96px"
+                                            alt=""
+                                        />
+                                    } else {
+                                        <img class="project-icon" src="assets/icons/executable-0.png" alt="" />
+                                    }
+                                    <span>{ project.title.clone() }</span>
+                                </a>
+                            </li>
+                        }
+                    }).collect::<Html>()
+                }
+            </ul>
+        },
+        ProjectLayout::Details => html! {
+            <table class="project-table">
+                <thead>
+                    <tr>
+                        <th>{ "Name" }</th>
+                        <th>{ "Year" }</th>
+                        <th>{ "Language" }</th>
+                        <th>{ "Stars" }</th>
+                        <th>{ "Last commit" }</th>
+                    </tr>
+                </thead>
+                <tbody>
+                    {
+                        props.matching.iter().map(|&id| {
+                            let project = &props.projects[id];
+                            html! {
+                                <tr>
+                                    <td>
+                                        <a href="#" onclick={open(id)}>{ project.title.clone() }</a>
+                                    </td>
+                                    <td>{ project.year.map(|y| y.to_string()).unwrap_or_default() }</td>
+                                    { stats_cells(stats(project)) }
+                                </tr>
+                            }
+                        }).collect::<Html>()
+                    }
+                </tbody>
+            </table>
+        },
+    }
+}
@@ -1,136 +1,282 @@
-use yew::{function_component, html, Callback, Properties, use_state};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::rc::Rc;
 
-#[derive(PartialEq, Eq, Clone)]
+use serde::Deserialize;
+use wasm_bindgen_futures::spawn_local;
+use web_sys::HtmlInputElement;
+use yew::{
+    function_component, html, use_effect_with_deps, use_reducer, use_state, Callback, Html,
+    InputEvent, Properties, Reducible, TargetCast,
+};
+
+use crate::github::{GitHub, RepoStats};
+use crate::markdown;
+use crate::time_format::RelativeTime;
+
+use super::project_views::{all_facets, ProjectLayout, ProjectList, TagFilter};
+use super::responsive_image::{fetch_variants, ImageInfo, ResponsiveImage, Variants};
+
+pub const SPLASHES_DIR: &str = "/assets/project_splashes";
+
+/// `content/projects.toml`, checked and converted to JSON by build.rs.
+const PROJECTS_JSON: &str = include_str!(concat!(env!("OUT_DIR"), "/projects.json"));
+
+#[derive(Deserialize, PartialEq, Eq, Clone, Debug)]
+pub struct ProjectLink {
+    pub label: String,
+    pub url: String,
+}
+
+#[derive(Deserialize, PartialEq, Eq, Clone, Debug)]
 pub struct ProjectData {
-    title: String,
-    splash_image: Option<String>,
-    description: String,
-    link: Option<String>,
+    pub title: String,
+    /// File name in `SPLASHES_DIR`.
+    pub splash: Option<String>,
+    /// Markdown.
+    pub description: String,
+    pub links: Vec<ProjectLink>,
+    pub tags: Vec<String>,
+    pub year: Option<u16>,
+    pub tech: Vec<String>,
+}
+
+impl ProjectData {
+    /// Stable identifier for this project, used to key its README window.
+    pub fn id(&self) -> usize {
+        let mut hasher = DefaultHasher::new();
+        self.title.hash(&mut hasher);
+        hasher.finish() as usize
+    }
+
+    /// "owner/name" of the first GitHub repository it links to.
+    pub fn github_repo(&self) -> Option<String> {
+        self.links.iter().find_map(|link| {
+            let path = link.url.strip_prefix("https://github.com/")?;
+            let mut parts = path.trim_end_matches('/').split('/');
+            match (parts.next(), parts.next(), parts.next()) {
+                (Some(owner), Some(name), None) if !owner.is_empty() && !name.is_empty() => {
+                    Some(format!("{}/{}", owner, name))
+                }
+                _ => None,
+            }
+        })
+    }
+
+    /// Its tags and tech stack, which are both offered as filters.
+    pub fn facets(&self) -> impl Iterator<Item = &String> {
+        self.tech.iter().chain(&self.tags)
+    }
+
+    /// Whether `query` appears anywhere in it and it has every one of `facets`.
+    pub fn matches(&self, query: &str, facets: &[String]) -> bool {
+        let query = query.trim().to_lowercase();
+        let text_matches = query.is_empty()
+            || self.title.to_lowercase().contains(&query)
+            || self.description.to_lowercase().contains(&query)
+            || self.facets().any(|f| f.to_lowercase().contains(&query));
+        text_matches
+            && facets
+                .iter()
+                .all(|wanted| self.facets().any(|f| f.eq_ignore_ascii_case(wanted)))
+    }
+}
+
+pub fn load_projects() -> Vec<ProjectData> {
+    serde_json::from_str(PROJECTS_JSON).expect("build.rs writes valid projects")
+}
+
+/// GitHub stats fetched so far, keyed by "owner/name".
+#[derive(Default)]
+struct RepoStatsMap(HashMap<String, RepoStats>);
+impl Reducible for RepoStatsMap {
+    type Action = (String, RepoStats);
+
+    fn reduce(self: Rc<Self>, (repo, stats): (String, RepoStats)) -> Rc<Self> {
+        let mut map = self.0.clone();
+        map.insert(repo, stats);
+        RepoStatsMap(map).into()
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct ProjectsProps {
+    #[prop_or_default]
+    pub github: GitHub,
+    pub open_readme: Callback<ProjectData>,
 }
 
+/// An Explorer style browser of the projects, opening one at a time.
 #[function_component(Projects)]
-pub fn projects() -> Html {
-    let projects: Vec<ProjectData> = vec![
-        ProjectData {
-            title: "Luna Bot".to_string(),
-            splash_image: Some("luna-bot.png".to_string()),
-            description: "
-                A Discord Bot which I spent multiple years developing.
-                Featuring it's own programming language, reminders, ranking, image manipulation and generation, YouTube and Twitch integrations, it was my biggest project.
-                It was mainly coded in python but for it's website I used JS, HTML, and CSS (NO FRAMEWORKS, in fact I made my own mini router for this).
-                It had a Postgres database which the website and bot interacted with via a RPC interface I coded with custom caching.
-                My one truly full stack project.
-            ".to_string(),
-            link: None,
-        },
-        ProjectData {
-            title: "Boo".to_string(),
-            splash_image: Some("boo.png".to_string()),
-            description: "
-                A halloween themed game in a 14 by 10 tile grid where there are enemies and puzzles to pass through.
-                Made for CSS GameJam 2021 (2nd Place 🎉) by
-                Roan Vickerman,
-                Amica Baxter,
-                Sankarsh Makam,
-                David Yan, and
-                Harleen Gulati
-            ".to_string(),
-            link: Some("https://github.com/14ROVI/Boo".to_string()),
-        },
-        ProjectData {
-            title: "Kit".to_string(),
-            splash_image: Some("kit.png".to_string()),
-            description: "
-                Kit was my entry into the 2022 CSS GameJam. Although it didn't win any prizes I learnt a lot about Rust and ECS game development in the process.
-                Either way it is a fun little proof of concept that you can make quick and simple platformer games using Rust.
-            ".to_string(),
-            link: Some("https://github.com/14ROVI/css-game-jam-2022".to_string()),
-        },
-        ProjectData {
-            title: "VS Twitter".to_string(),
-            splash_image: Some("vs-twitter.png".to_string()),
-            description: "
-                VS Twitter is a Discord App which allows people to get the media URL of Twitter GIFs or videos directly. It was my first experience with CloudFlare
-                workers and TypeScript which proved to be a very pleasant experience.
-            ".to_string(),
-            link: Some("https://github.com/14ROVI/vs-twitter".to_string()),
-        },
-        ProjectData {
-            title: "LunaScript".to_string(),
-            splash_image: None,
-            description: "
-                In the past I ran a Discord bot called Luna and people would ask me to implement lots of random features. Of course, this is not theasable
-                and would break up my code base a ton for rarely used code. So, I learnt how to make programming languages and created my own so people
-                can use that to add their own code which would be executed. I needed to do this instead of restricted environments because they still have
-                vulnerabilities (especially in Python) and It meant I could limit API calls and such easier.
-            ".to_string(),
-            link: Some("https://github.com/14ROVI/luna_script".to_string()),
-        },
-        ProjectData {
-            title: "Link Shortener (Rust)".to_string(),
-            splash_image: Some("link-shortener-rs.png".to_string()),
-            description: "
-                A simple link shortener project which I made in Rust.
-            ".to_string(),
-            link: Some("https://github.com/14ROVI/link-shortener-rs".to_string()),
-        },
-        ProjectData {
-            title: "Spotify playlist to video".to_string(),
-            splash_image: Some("spotify-mv-maker.png".to_string()),
-            description: "
-                I once wanted to be a youtuber who uploaded cool playlists which would get millions of views. Unfortunately, that was short lived but in the
-                process I created this script which would automatically generate the video from a Spotify playlist URL for me!
-            ".to_string(),
-            link: Some("https://github.com/14ROVI/playlist_video".to_string()),
-        },
-        ProjectData {
-            title: "GIF Decoder".to_string(),
-            splash_image: Some("gif-decoder.png".to_string()),
-            description: "
-                I learnt how to interpret the binary of GIF files and wrote a Python script to create the bitmap data for a GIF. This was part of a wider project
-                to store the Bad Apple video in a game and then convert my Python code into the game's visual scripting language to render it on a space craft.
-            ".to_string(),
-            link: Some("https://github.com/14ROVI/gif_decoder".to_string()),
-        },
-        ProjectData {
-            title: "And probably others I've forgotten to mention".to_string(),
-            splash_image: None,
-            description: "
-                I have done a lot of coding in my life so far and a lot of these projects I've either done randomly for a one off task and
-                forgot to put it on my GitHub or just didn't care enough. If you want to check out my GitHub at https://github.com/14ROVI/
-                there are probably projects omitted from this list.
-            ".to_string(),
-            link: Some("https://github.com/14ROVI/".to_string()),
-        }
-    ];
-
-    let project_id = use_state(|| 0);
-    let decrement =  {
-        let project_id = project_id.clone();
-        let new_project_id = if *project_id == 0 {
-            projects.len() - 1
-        } else {
-            *project_id - 1
-        };
-        Callback::from(move |_| project_id.set(new_project_id))
+pub fn projects(props: &ProjectsProps) -> Html {
+    let projects = use_state(load_projects);
+    let query = use_state(String::new);
+    let selected_facets = use_state(Vec::<String>::new);
+    let layout = use_state(|| ProjectLayout::Icons);
+    let open = use_state(|| None::<usize>);
+    let stats = use_reducer(RepoStatsMap::default);
+    let splashes = use_state(Variants::new);
+    {
+        let splashes = splashes.clone();
+        use_effect_with_deps(
+            move |_| {
+                spawn_local(async move {
+                    splashes.set(fetch_variants(SPLASHES_DIR).await);
+                });
+                || ()
+            },
+            (),
+        );
+    }
+    {
+        let stats = stats.clone();
+        let github = props.github.clone();
+        let repos: Vec<String> = projects.iter().filter_map(|p| p.github_repo()).collect();
+        use_effect_with_deps(
+            move |_| {
+                for repo in repos {
+                    let request = github.0.repo(&repo);
+                    let stats = stats.clone();
+                    spawn_local(async move {
+                        match request.await {
+                            Ok(found) => stats.dispatch((repo, found)),
+                            Err(e) => log::warn!("Couldn't get GitHub stats for {}: {}", repo, e),
+                        }
+                    });
+                }
+                || ()
+            },
+            (),
+        );
+    }
+
+    let matching: Vec<usize> = projects
+        .iter()
+        .enumerate()
+        .filter(|(_, p)| p.matches(&query, &selected_facets))
+        .map(|(id, _)| id)
+        .collect();
+
+    let open_project = {
+        let open = open.clone();
+        Callback::from(move |id: usize| open.set(Some(id)))
     };
-    let increment =  {
-        let project_id = project_id.clone();
-        let new_project_id = if *project_id == projects.len() - 1 {
-            0
-        } else {
-            *project_id + 1
+
+    if let Some(id) = *open {
+        // Step through what was matching when it was opened.
+        let position = matching.iter().position(|&i| i == id).unwrap_or(0);
+        let step = |by: isize| {
+            let open = open.clone();
+            let len = matching.len().max(1) as isize;
+            let next = matching
+                .get((position as isize + by).rem_euclid(len) as usize)
+                .copied()
+                .unwrap_or(id);
+            Callback::from(move |_| open.set(Some(next)))
+        };
+        let back = {
+            let open = open.clone();
+            Callback::from(move |_| open.set(None))
+        };
+        let project = &projects[id];
+        let open_readme = {
+            let open_readme = props.open_readme.clone();
+            let project = project.clone();
+            Callback::from(move |_| open_readme.emit(project.clone()))
+        };
+
+        return html!{
+            <div class="project-explorer">
+                <div class="project-explorer-toolbar">
+                    <button onclick={back}>{"Back"}</button>
+                    if project.github_repo().is_some() {
+                        <button onclick={open_readme}>{"Read me"}</button>
+                    }
+                    <span>{format!("{} of {}", position + 1, matching.len())}</span>
+                </div>
+                <div style="display:flex; gap: 5px; align-items: center;">
+                    <button style="min-width: unset; align-self: stretch;" onclick={step(-1)} aria-label="Previous project">{"<"}</button>
+                    <div style="font-size: 12px;">
+                        <Project
+                            project={project.clone()}
+                            splash={project.splash.as_ref().and_then(|s| splashes.get(s)).cloned()}
+                            stats={project.github_repo().and_then(|r| stats.0.get(&r)).cloned()}
+                        />
+                    </div>
+                    <button style="min-width: unset; align-self: stretch;" onclick={step(1)} aria-label="Next project">{">"}</button>
+                </div>
+            </div>
         };
-        Callback::from(move |_| project_id.set(new_project_id))
+    }
+
+    let on_search = {
+        let query = query.clone();
+        Callback::from(move |e: InputEvent| {
+            query.set(e.target_unchecked_into::<HtmlInputElement>().value());
+        })
+    };
+    let on_toggle_facet = {
+        let selected_facets = selected_facets.clone();
+        Callback::from(move |facet: String| {
+            let mut facets = (*selected_facets).clone();
+            match facets.iter().position(|f| *f == facet) {
+                Some(i) => {
+                    facets.remove(i);
+                }
+                None => facets.push(facet),
+            }
+            selected_facets.set(facets);
+        })
     };
 
     html!{
-        <div style="display:flex; gap: 5px; align-items: center;">
-            <button style="min-width: unset; align-self: stretch;" onclick={decrement}>{"<"}</button>
-            <div style="font-size: 12px;">
-                <Project project={projects[*project_id].clone()}/>
+        <div class="project-explorer">
+            <div class="project-explorer-toolbar">
+                <input
+                    type="search"
+                    placeholder="Search projects"
+                    aria-label="Search projects"
+                    value={(*query).clone()}
+                    oninput={on_search}
+                />
+                <div class="field-row">
+                    {
+                        ProjectLayout::ALL.iter().map(|mode| {
+                            let mode = *mode;
+                            let layout = layout.clone();
+                            html!{
+                                <button
+                                    aria-pressed={(*layout == mode).to_string()}
+                                    onclick={Callback::from(move |_| layout.set(mode))}
+                                >
+                                    {mode.label()}
+                                </button>
+                            }
+                        }).collect::<Html>()
+                    }
+                </div>
+            </div>
+            <TagFilter
+                facets={all_facets(&projects)}
+                selected={(*selected_facets).clone()}
+                on_toggle={on_toggle_facet}
+            />
+            <div class="project-results">
+                <ProjectList
+                    matching={matching.clone()}
+                    projects={(*projects).clone()}
+                    layout={*layout}
+                    stats={stats.0.clone()}
+                    splashes={(*splashes).clone()}
+                    {open_project}
+                />
+            </div>
+            <div class="status-bar">
+                <p class="status-bar-field">
+                    {format!("{} of {} projects", matching.len(), projects.len())}
+                </p>
             </div>
-            <button style="min-width: unset; align-self: stretch;" onclick={increment}>{">"}</button>
         </div>
     }
 }
@@ -139,26 +285,64 @@ pub fn projects() -> Html {
 #[derive(Properties, PartialEq, Eq)]
 pub struct ProjectProps {
     pub project: ProjectData,
+    #[prop_or_default]
+    pub splash: Option<ImageInfo>,
+    #[prop_or_default]
+    pub stats: Option<RepoStats>,
 }
 
 #[function_component(Project)]
 pub fn project(props: &ProjectProps) -> Html {
     let project = &props.project;
-    let href = project.link.clone();
+    let href = project.links.first().map(|link| link.url.clone());
+    let meta: Vec<String> = project
+        .year
+        .map(|year| year.to_string())
+        .into_iter()
+        .chain(project.tech.iter().cloned())
+        .collect();
 
     html!{
         <>
             <a style="color: inherit; text-decoration: none;" {href} target="_blank" rel="noopener noreferrer">
                 <h3 style="margin: 0 0 5px 0">{project.title.clone()}</h3>
-                {
-                    if let Some(splash_image) = &project.splash_image {
-                        html!{ <img style="width: 100%; margin: 0 auto; display: block;" src={format!("/assets/project_splashes/{}", splash_image.clone())} /> }
-                    } else {
-                        html!{}
-                    }
+                if let Some(splash) = &project.splash {
+                    <ResponsiveImage
+                        style="width: 100%; height: auto; margin: 0 auto; display: block;"
+                        dir={SPLASHES_DIR}
+                        file={splash.clone()}
+                        info={props.splash.clone()}
+                        sizes="500px"
+                        alt={project.title.clone()}
+                    />
                 }
             </a>
-            <p>{project.description.clone()}</p>
+            if !meta.is_empty() {
+                <p class="project-meta">{meta.join(" · ")}</p>
+            }
+            if let Some(stats) = &props.stats {
+                <p class="project-meta">
+                    {format!("★ {} · ", stats.stars)}
+                    if let Some(language) = &stats.language {
+                        {format!("{} · ", language)}
+                    }
+                    {"Last commit "}
+                    <RelativeTime timestamp={stats.pushed_timestamp()} />
+                </p>
+            }
+            <div class="project-description">
+                {markdown::render(&project.description)}
+            </div>
+            if !project.links.is_empty() || !project.tags.is_empty() {
+                <div class="project-footer">
+                    {for project.links.iter().map(|link| html!{
+                        <a href={link.url.clone()} target="_blank" rel="noopener noreferrer">{link.label.clone()}</a>
+                    })}
+                    <ul class="project-tags">
+                        {for project.tags.iter().map(|tag| html!{ <li>{tag.clone()}</li> })}
+                    </ul>
+                </div>
+            }
         </>
     }
-}
\ No newline at end of file
+}
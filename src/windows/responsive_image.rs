@@ -0,0 +1,141 @@
+use std::collections::HashMap;
+
+use gloo_net::http::Request;
+use serde::Deserialize;
+use yew::{function_component, html, Callback, Classes, MouseEvent, Properties};
+
+/// A resized copy of an image written by `tools/gallery`.
+#[derive(Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct Variant {
+    /// Path relative to the original image's directory.
+    pub file: String,
+    pub width: u32,
+    /// One of "avif", "webp" or "jpeg".
+    pub format: String,
+}
+
+#[derive(Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct ImageInfo {
+    pub width: u32,
+    pub height: u32,
+    /// A tiny JPEG data URL to show blurred while the real image loads.
+    pub placeholder: String,
+    pub variants: Vec<Variant>,
+}
+impl ImageInfo {
+    fn srcset(&self, dir: &str, format: &str) -> String {
+        self.variants
+            .iter()
+            .filter(|v| v.format == format)
+            .map(|v| format!("{}/{} {}w", dir, v.file, v.width))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    fn smallest(&self, format: &str) -> Option<&Variant> {
+        self.variants
+            .iter()
+            .filter(|v| v.format == format)
+            .min_by_key(|v| v.width)
+    }
+}
+
+/// Original file name to its generated variants.
+pub type Variants = HashMap<String, ImageInfo>;
+
+/// Loads the variants manifest for `dir`. It only exists once `tools/gallery`
+/// has run, so without it images are just served at full size.
+pub async fn fetch_variants(dir: &str) -> Variants {
+    let resp = match Request::get(&format!("{}/variants/manifest.json", dir))
+        .send()
+        .await
+    {
+        Ok(resp) if resp.ok() => resp,
+        _ => {
+            log::info!("No image variants for {}, using originals", dir);
+            return Variants::new();
+        }
+    };
+    resp.json().await.unwrap_or_else(|e| {
+        log::error!("Couldn't parse image variants for {}: {}", dir, e);
+        Variants::new()
+    })
+}
+
+#[derive(Properties, PartialEq)]
+pub struct ResponsiveImageProps {
+    pub dir: String,
+    pub file: String,
+    #[prop_or_default]
+    pub info: Option<ImageInfo>,
+    /// The `sizes` attribute, how wide the image is drawn.
+    pub sizes: String,
+    #[prop_or_default]
+    pub alt: String,
+    #[prop_or_default]
+    pub title: Option<String>,
+    #[prop_or_default]
+    pub class: Classes,
+    #[prop_or_default]
+    pub style: Option<String>,
+    #[prop_or_default]
+    pub onclick: Callback<MouseEvent>,
+    #[prop_or_default]
+    pub onerror: Callback<yew::Event>,
+}
+
+/// A lazily loaded `<picture>` offering AVIF and WebP variants, falling back
+/// to the original file when there aren't any.
+#[function_component(ResponsiveImage)]
+pub fn responsive_image(props: &ResponsiveImageProps) -> Html {
+    let original = format!("{}/{}", props.dir, props.file);
+
+    let info = match &props.info {
+        Some(info) => info,
+        None => {
+            return html! {
+                <img
+                    class={props.class.clone()}
+                    style={props.style.clone()}
+                    src={original}
+                    alt={props.alt.clone()}
+                    title={props.title.clone()}
+                    loading="lazy"
+                    onclick={props.onclick.clone()}
+                    onerror={props.onerror.clone()}
+                />
+            }
+        }
+    };
+
+    let src = info
+        .smallest("jpeg")
+        .map_or(original, |v| format!("{}/{}", props.dir, v.file));
+    let style = format!(
+        "background-image: url({}); {}",
+        info.placeholder,
+        props.style.clone().unwrap_or_default()
+    );
+
+    html! {
+        <picture>
+            <source type="image/avif" srcset={info.srcset(&props.dir, "avif")} sizes={props.sizes.clone()} />
+            <source type="image/webp" srcset={info.srcset(&props.dir, "webp")} sizes={props.sizes.clone()} />
+            <img
+                class={props.class.clone()}
+                {style}
+                {src}
+                srcset={info.srcset(&props.dir, "jpeg")}
+                sizes={props.sizes.clone()}
+                width={info.width.to_string()}
+                height={info.height.to_string()}
+                alt={props.alt.clone()}
+                title={props.title.clone()}
+                loading="lazy"
+                decoding="async"
+                onclick={props.onclick.clone()}
+                onerror={props.onerror.clone()}
+            />
+        </picture>
+    }
+}
@@ -0,0 +1,51 @@
+use gloo::timers::callback::Interval;
+use wasm_bindgen::JsCast;
+use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement};
+use yew::{function_component, html, use_effect_with_deps, use_node_ref, Properties};
+
+use crate::screensavers::{Animation, Saver};
+
+/// About 30 frames a second, which is plenty for a screensaver.
+const FRAME_MS: u32 = 33;
+
+#[derive(Properties, PartialEq)]
+pub struct ScreenSaverProps {
+    pub saver: Saver,
+    /// Scrolled by the marquee.
+    #[prop_or_default]
+    pub text: String,
+}
+
+/// Runs `saver` on a canvas that fills its parent.
+#[function_component(ScreenSaver)]
+pub fn screen_saver(props: &ScreenSaverProps) -> Html {
+    let canvas = use_node_ref();
+
+    {
+        let canvas = canvas.clone();
+        use_effect_with_deps(
+            move |(saver, text)| {
+                let interval = canvas.cast::<HtmlCanvasElement>().and_then(|canvas| {
+                    let context: CanvasRenderingContext2d =
+                        canvas.get_context("2d").ok()??.dyn_into().ok()?;
+                    let mut animation = Animation::new(*saver, text);
+                    Some(Interval::new(FRAME_MS, move || {
+                        let width = canvas.client_width().max(1) as u32;
+                        let height = canvas.client_height().max(1) as u32;
+                        if canvas.width() != width || canvas.height() != height {
+                            canvas.set_width(width);
+                            canvas.set_height(height);
+                        }
+                        animation.step(&context, width as f64, height as f64);
+                    }))
+                });
+                move || drop(interval)
+            },
+            (props.saver, props.text.clone()),
+        );
+    }
+
+    html! {
+        <canvas class="screensaver-canvas" ref={canvas}></canvas>
+    }
+}
@@ -1,5 +1,7 @@
 use yew::{function_component, html};
 
+use crate::DISCORD_USER_ID;
+
 #[function_component(Socials)]
 pub fn socials() -> Html {
     html!{
@@ -16,7 +18,7 @@ pub fn socials() -> Html {
                 <img alt="Spotify logo" src="/assets/icons/spotify.svg" style="width: 30px; height: 30px;"/>
             </a>
 
-            <a name="My Discord" href="https://discord.com/users/195512978634833920" target="_blank" rel="noopener noreferrer">
+            <a name="My Discord" href={format!("https://discord.com/users/{}", DISCORD_USER_ID)} target="_blank" rel="noopener noreferrer">
                 <img alt="Discord logo" src="/assets/icons/discord.svg" style="width: 30px; height: 30px;"/>
             </a>
 
@@ -1,145 +1,198 @@
 use std::vec;
 
-use futures::{channel::mpsc::UnboundedSender, SinkExt, StreamExt};
 use gloo::net::http::Request;
-use gloo::net::websocket::{futures::WebSocket, Message as WsMessage};
 use gloo::timers::callback::Interval;
-use js_sys::Date;
+use gloo::storage::{LocalStorage, Storage};
 use serde_json::Value;
 use wasm_bindgen_futures::spawn_local;
 use yew::prelude::*;
 
+use super::lyrics::Lyrics;
+use super::track_progress::TrackProgress;
+use crate::colour::{palette_from_image, Accent};
+use crate::copland::Notification;
+use crate::lanyard::{self, LanyardConnection, Presence};
+use crate::time_format::RelativeTime;
+use crate::DISCORD_USER_ID;
+
+const NOTIFICATIONS_KEY: &str = "spotify-notifications";
+
 struct LanyardData {
     album_art: String,
     song_name: String,
     album_name: String,
     artist_name: String,
-    start_time: u64,
-    end_time: u64,
+    start_time: Option<u64>,
+    end_time: Option<u64>,
 }
 
 #[derive(Properties, PartialEq)]
 pub struct SpotifyProperties {
     pub resize_window: Callback<Option<u32>>,
+    pub set_accent: Callback<Option<Accent>>,
+    pub notify: Callback<Notification>,
 }
 
 #[derive(Debug)]
 pub enum Msg {
-    LanyardMessage(WsMessage),
-    UpdateTime,
+    /// Boxed as it's much bigger than the other messages.
+    Presence(Box<Presence>),
     UpdateHistory,
     SaveHistory(Vec<LastFmHistoryHOCProps>),
     ToggleShowHistory,
+    ToggleShowLyrics,
+    ToggleNotifications,
+    /// The accent worked out from the given album art.
+    SetAccent(String, Option<Accent>),
 }
 
 pub struct Spotify {
-    lanyard_ws_write: UnboundedSender<String>,
+    _lanyard: Option<LanyardConnection>,
     lanyard_data: Option<LanyardData>,
-    update_timer: Option<Interval>,
+    received_presence: bool,
+    notifications_enabled: bool,
     show_history: bool,
+    show_lyrics: bool,
     history: Vec<LastFmHistoryHOCProps>,
     last_fm_current: Option<LastFmHistoryHOCProps>,
+    accent_source: Option<String>,
+    /// The song and artist last seen playing, kept through pauses so resuming
+    /// the same song doesn't announce it again.
+    last_track: Option<(String, String)>,
 }
-impl Component for Spotify {
-    type Message = Msg;
-    type Properties = SpotifyProperties;
+impl Spotify {
+    fn resize(&self, ctx: &Context<Spotify>) {
+        let expanded = self.show_history || self.show_lyrics;
+        ctx.props().resize_window.emit(expanded.then_some(350));
+    }
 
-    fn create(ctx: &Context<Self>) -> Self {
-        let ws = WebSocket::open("wss://api.lanyard.rest/socket")
-            .expect("Couldn't connect to Lanyard ws.");
-        let (mut write, mut read) = ws.split();
-        let (tx, mut rx) = futures::channel::mpsc::unbounded::<String>();
+    /// Re-themes the window from the album art whenever the art changes.
+    fn update_accent(&mut self, ctx: &Context<Spotify>) {
+        let album_art = self
+            .lanyard_data
+            .as_ref()
+            .map(|d| d.album_art.clone())
+            .or_else(|| self.last_fm_current.as_ref().map(|c| c.album_art.clone()))
+            .filter(|art| !art.is_empty());
+
+        if album_art == self.accent_source {
+            return;
+        }
+        self.accent_source = album_art.clone();
 
-        // TODO: reconnection handling incase of error
-        // TODO: serde structs rather than json!!!
+        match album_art {
+            Some(album_art) => {
+                let link = ctx.link().clone();
+                spawn_local(async move {
+                    let accent = palette_from_image(&album_art, 6)
+                        .await
+                        .and_then(|palette| Accent::from_palette(&palette));
+                    link.send_message(Msg::SetAccent(album_art, accent));
+                })
+            }
+            None => ctx.props().set_accent.emit(None),
+        }
+    }
 
-        let on_lanyard_message = ctx.link().callback(Msg::LanyardMessage);
-        spawn_local(async move {
-            while let Some(Ok(msg)) = read.next().await {
-                on_lanyard_message.emit(msg);
+    /// Pops up a taskbar balloon when the song changes, unless silenced.
+    fn notify_track_change(&mut self, ctx: &Context<Spotify>) {
+        if let Some(lanyard_data) = self.lanyard_data.as_ref() {
+            let current = (
+                lanyard_data.song_name.clone(),
+                lanyard_data.artist_name.clone(),
+            );
+            if self.last_track.as_ref() == Some(&current) {
+                return;
+            }
+            if self.received_presence && self.notifications_enabled {
+                ctx.props().notify.emit(Notification {
+                    title: "Now playing".to_string(),
+                    body: format!("{} by {}", current.0, current.1),
+                    icon: "assets/icons/spotify.svg".to_string(),
+                });
             }
-            log::info!("Lanyard ws closed.");
-        });
+            self.last_track = Some(current);
+        }
+    }
 
-        spawn_local(async move {
-            while let Some(msg) = rx.next().await {
-                log::info!("sent {}", &msg);
-                write.send(WsMessage::Text(msg)).await.unwrap();
+    fn view_lyrics(&self) -> Html {
+        if let Some(lanyard_data) = self.lanyard_data.as_ref() {
+            html! {
+                <Lyrics
+                    song={lanyard_data.song_name.clone()}
+                    artist={lanyard_data.artist_name.clone()}
+                    album={lanyard_data.album_name.clone()}
+                    start_time={lanyard_data.start_time}
+                    end_time={lanyard_data.end_time}
+                />
+            }
+        } else if let Some(last_fm_current) = self.last_fm_current.as_ref() {
+            html! {
+                <Lyrics
+                    song={last_fm_current.song.clone()}
+                    artist={last_fm_current.artist.clone()}
+                    album={last_fm_current.album.clone()}
+                    start_time={None}
+                    end_time={None}
+                />
             }
-        });
+        } else {
+            html! {
+                <p>{ "Nothing playing, so no lyrics!" }</p>
+            }
+        }
+    }
+}
+impl Component for Spotify {
+    type Message = Msg;
+    type Properties = SpotifyProperties;
+
+    fn create(ctx: &Context<Self>) -> Self {
+        let on_presence = ctx
+            .link()
+            .callback(|presence| Msg::Presence(Box::new(presence)));
+        let lanyard = lanyard::connect(DISCORD_USER_ID, on_presence);
 
         let link = ctx.link().clone();
         link.send_message(Msg::UpdateHistory);
         Interval::new(10_000, move || link.send_message(Msg::UpdateHistory)).forget();
 
         Self {
-            lanyard_ws_write: tx,
+            _lanyard: lanyard,
             lanyard_data: None,
-            update_timer: None,
+            received_presence: false,
+            notifications_enabled: LocalStorage::get(NOTIFICATIONS_KEY).unwrap_or(true),
             show_history: false,
+            show_lyrics: false,
             history: vec![],
             last_fm_current: None,
+            accent_source: None,
+            last_track: None,
         }
     }
 
     fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
         match msg {
-            Msg::LanyardMessage(WsMessage::Text(data)) => {
-                let data: Value = serde_json::from_str(&data).expect("Not JSON");
-                let op = data["op"].as_u64().unwrap();
-
-                if op == 1 {
-                    log::info!("gotta start heartbeat");
-
-                    let mut tx = self.lanyard_ws_write.clone();
-                    let heartbeat_duration =
-                        data["d"]["heartbeat_interval"].as_u64().unwrap_or(30_000);
-                    spawn_local(async move {
-                        tx.send(
-                            r#"{ "op": 2, "d": { "subscribe_to_id": "195512978634833920" } }"#
-                                .to_string(),
-                        )
-                        .await
-                        .unwrap();
+            Msg::Presence(presence) => {
+                log::info!("lanyard actual useful data: {:?}", presence);
+
+                self.lanyard_data = presence
+                    .spotify
+                    .filter(|_| presence.listening_to_spotify)
+                    .map(|spotify| LanyardData {
+                        album_art: spotify.album_art_url,
+                        song_name: spotify.song,
+                        album_name: spotify.album,
+                        artist_name: spotify.artist,
+                        start_time: spotify.timestamps.start,
+                        end_time: spotify.timestamps.end,
                     });
-
-                    let tx = self.lanyard_ws_write.clone();
-                    Interval::new(heartbeat_duration as u32, move || {
-                        let mut tx = tx.clone();
-                        spawn_local(async move {
-                            tx.send(r#"{ "op": 3 }"#.to_string()).await.unwrap_or(());
-                        });
-                    })
-                    .forget();
-                } else {
-                    log::info!("lanyard actual useful data: {:?}", data);
-                    let data = &data["d"];
-
-                    if data["listening_to_spotify"].as_bool().unwrap_or(false) {
-                        self.lanyard_data = Some(LanyardData {
-                            album_art: data["spotify"]["album_art_url"]
-                                .as_str()
-                                .unwrap()
-                                .to_string(),
-                            song_name: data["spotify"]["song"].as_str().unwrap().to_string(),
-                            album_name: data["spotify"]["album"].as_str().unwrap().to_string(),
-                            artist_name: data["spotify"]["artist"].as_str().unwrap().to_string(),
-                            start_time: data["spotify"]["timestamps"]["start"].as_u64().unwrap(),
-                            end_time: data["spotify"]["timestamps"]["end"].as_u64().unwrap(),
-                        });
-                        let link = ctx.link().clone();
-                        self.update_timer = Some(Interval::new(1_000, move || {
-                            link.send_message(Msg::UpdateTime)
-                        }));
-                    } else {
-                        self.lanyard_data = None;
-                        self.update_timer = None;
-                    }
-                }
+                self.update_accent(ctx);
+                self.notify_track_change(ctx);
+                self.received_presence = true;
 
                 true
             }
-            Msg::UpdateTime => true,
             Msg::UpdateHistory => {
                 let save_history = ctx.link().callback(Msg::SaveHistory);
 
@@ -158,7 +211,6 @@ impl Component for Spotify {
                         .map(|t| {
                             let t = t.clone();
                             LastFmHistoryHOCProps {
-                                current_time: Date::now() as u64 / 1000,
                                 album_art: t["image"][3]["#text"]
                                     .as_str()
                                     .unwrap_or_default()
@@ -190,36 +242,51 @@ impl Component for Spotify {
                     self.last_fm_current = None;
                 }
                 self.history = history;
+                self.update_accent(ctx);
                 true
             }
             Msg::ToggleShowHistory => {
                 self.show_history = !self.show_history;
-                ctx.props().resize_window.emit(self.show_history.then_some(350));
+                self.resize(ctx);
+                true
+            }
+            Msg::ToggleNotifications => {
+                self.notifications_enabled = !self.notifications_enabled;
+                LocalStorage::set(NOTIFICATIONS_KEY, self.notifications_enabled).ok();
                 true
             }
-            _ => {
-                log::info!("not covered!");
+            Msg::ToggleShowLyrics => {
+                self.show_lyrics = !self.show_lyrics;
+                self.resize(ctx);
+                true
+            }
+            Msg::SetAccent(album_art, accent) => {
+                // Drop palettes for art that's been replaced while they loaded.
+                if self.accent_source.as_ref() == Some(&album_art) {
+                    ctx.props().set_accent.emit(accent);
+                }
                 false
             }
         }
     }
 
     fn view(&self, ctx: &Context<Self>) -> Html {
-        let current_time = Date::now() as u64 / 1000;
         let toggle_show_history = ctx.link().callback(|_| Msg::ToggleShowHistory);
         let button_open = self.show_history.then_some("open");
-
-       let currently_playing = if let Some(lanyard_data) = self.lanyard_data.as_ref() {
-            let current_time = Date::now() as u64;
-            let elapsed_time = (current_time - lanyard_data.start_time) / 1000;
-            let total_time = (lanyard_data.end_time - lanyard_data.start_time) / 1000;
-
-            fn format_time(t: u64) -> String {
-                let mins = t / 60;
-                let seconds = t % 60;
-                format!("{}:{:02}", mins, seconds)
-            }
-
+        let toggle_show_lyrics = ctx.link().callback(|_| Msg::ToggleShowLyrics);
+        let lyrics_button_open = self.show_lyrics.then_some("open");
+
+        let toggle_notifications = ctx.link().callback(|_| Msg::ToggleNotifications);
+
+        let currently_playing = if let Some(lanyard_data) = self.lanyard_data.as_ref() {
+            let progress = match (lanyard_data.start_time, lanyard_data.end_time) {
+                (Some(start_time), Some(end_time)) => html! {
+                    <TrackProgress {start_time} {end_time} />
+                },
+                _ => html! {
+                    <p id="spotify-song-duration">{"Currently listening"}</p>
+                },
+            };
             html! {
                 <div class="spotify-container">
                     <img alt="Spotify album art" width="100" height="100" src={ lanyard_data.album_art.clone() }/>
@@ -227,7 +294,7 @@ impl Component for Spotify {
                         <p><b>{ lanyard_data.song_name.clone() }</b></p>
                         <p>{ "On " }{ lanyard_data.album_name.clone() }</p>
                         <p>{ "By " }{ lanyard_data.artist_name.clone() }</p>
-                        <p id="spotify-song-duration">{ "Elapsed: " }{ format_time(elapsed_time) }{" / "}{ format_time(total_time) }</p>
+                        { progress }
                     </div>
                 </div>
             }
@@ -252,9 +319,28 @@ impl Component for Spotify {
         return html! {
             <>
                 { currently_playing }
+                <div class="field-row spotify-notifications">
+                    <input
+                        type="checkbox"
+                        id="spotify-notifications"
+                        checked={self.notifications_enabled}
+                        onchange={toggle_notifications}
+                    />
+                    <label for="spotify-notifications">{ "Notify me when the song changes" }</label>
+                </div>
+                <button
+                    id="lyrics-button"
+                    class={classes!("expand-button", lyrics_button_open)}
+                    onclick={toggle_show_lyrics}
+                >
+                    {"Lyrics"}
+                </button>
+                if self.show_lyrics {
+                    { self.view_lyrics() }
+                }
                 <button
                     id="lastfm-history-button"
-                    class={classes!(button_open)}
+                    class={classes!("expand-button", button_open)}
                     onclick={toggle_show_history}
                 >
                     {"History"}
@@ -268,7 +354,6 @@ impl Component for Spotify {
                                 .map(|p| html! {
                                     <LastFmHistoryHOC
                                         key={p.listened_at}
-                                        {current_time}
                                         ..p.clone()
                                     />
                                 } ).collect::<Html>() 
@@ -283,7 +368,6 @@ impl Component for Spotify {
 
 #[derive(Properties, PartialEq, Debug, Clone)]
 pub struct LastFmHistoryHOCProps {
-    pub current_time: u64,
     pub album_art: String,
     pub album: String,
     pub song: String,
@@ -296,57 +380,17 @@ pub struct LastFmHistoryProps {
     pub album_art: String,
     pub song: String,
     pub artist: String,
-    pub formatted_time: String,
+    pub listened_at: u64,
 }
 
 #[function_component(LastFmHistoryHOC)]
 pub fn last_fm_history_hoc(props: &LastFmHistoryHOCProps) -> Html {
-    let elapsed = props.current_time - props.listened_at;
-
-    let s_per_minute = 60;
-    let s_per_hour = 3600;
-    let s_per_day = 86400;
-    let s_per_month = 2592000;
-    let s_per_year = 31536000;
-
-    let minutes_passed = elapsed / s_per_minute;
-    let hours_passed = elapsed / s_per_hour;
-    let days_passed = elapsed / s_per_day;
-    let months_passed = elapsed / s_per_month;
-    let years_passed = elapsed / s_per_year;
-
-    let formatted_time = if years_passed > 1 {
-        format!("{} years ago", years_passed)
-    } else if years_passed == 1 {
-        String::from("1 year ago")
-    } else if months_passed > 1 {
-        format!("{} months ago", months_passed)
-    } else if months_passed == 1 {
-        String::from("1 month ago")
-    } else if days_passed > 1 {
-        format!("{} days ago", days_passed)
-    } else if days_passed == 1 {
-        String::from("1 day ago")
-    } else if hours_passed > 1 {
-        format!("{} hours ago", hours_passed)
-    } else if hours_passed == 1 {
-        String::from("1 hour ago")
-    } else if minutes_passed > 1 {
-        format!("{} minutes ago", minutes_passed)
-    } else if minutes_passed == 1 {
-        String::from("1 minute ago")
-    } else if elapsed > 1 {
-        format!("{} seconds ago", elapsed)
-    } else {
-        String::from("1 second ago")
-    };
-
     html! {
         <LastFmHistory
             album_art={props.album_art.clone()}
             song={props.song.clone()}
             artist={props.artist.clone()}
-            formatted_time={formatted_time}
+            listened_at={props.listened_at}
         />
     }
 }
@@ -364,7 +408,7 @@ pub fn last_fm_history(props: &LastFmHistoryProps) -> Html {
             <div>
                 <p><b>{ props.song.clone() }</b></p>
                 <p>{ props.artist.clone() }</p>
-                <p>{ props.formatted_time.clone() }</p>
+                <p><RelativeTime timestamp={props.listened_at} /></p>
             </div>
         </div>
     }
@@ -0,0 +1,199 @@
+use gloo::timers::callback::Interval;
+use js_sys::Date;
+use yew::{
+    classes, function_component, html, use_effect_with_deps, use_state, Callback, Html,
+    Properties,
+};
+
+use crate::lanyard::{self, Activity, Presence};
+use crate::DISCORD_USER_ID;
+
+fn status_label(status: &str) -> &'static str {
+    match status {
+        "online" => "Online",
+        "idle" => "Idle",
+        "dnd" => "Do Not Disturb",
+        _ => "Offline",
+    }
+}
+
+fn format_elapsed(seconds: u64) -> String {
+    let hours = seconds / 3600;
+    let mins = (seconds % 3600) / 60;
+    let seconds = seconds % 60;
+    if hours > 0 {
+        format!("{}:{:02}:{:02}", hours, mins, seconds)
+    } else {
+        format!("{}:{:02}", mins, seconds)
+    }
+}
+
+#[derive(Properties, PartialEq, Eq)]
+pub struct ElapsedProps {
+    /// Unix milliseconds the activity started.
+    pub start: u64,
+}
+
+#[function_component(Elapsed)]
+pub fn elapsed(props: &ElapsedProps) -> Html {
+    let now = use_state(Date::now);
+
+    {
+        let now = now.clone();
+        use_effect_with_deps(
+            move |_| {
+                let interval = Interval::new(1_000, move || now.set(Date::now()));
+                move || drop(interval)
+            },
+            (),
+        );
+    }
+
+    let seconds = (*now as u64).saturating_sub(props.start) / 1000;
+
+    html! {
+        <p>{ format_elapsed(seconds) }{ " elapsed" }</p>
+    }
+}
+
+#[derive(Properties, PartialEq, Eq)]
+pub struct ActivityProps {
+    pub activity: Activity,
+}
+
+#[function_component(ActivityCard)]
+pub fn activity_card(props: &ActivityProps) -> Html {
+    let activity = &props.activity;
+    let assets = activity.assets.clone().unwrap_or_default();
+
+    let large_image = assets
+        .large_image
+        .as_deref()
+        .and_then(|asset| activity.asset_url(asset));
+    let small_image = assets
+        .small_image
+        .as_deref()
+        .and_then(|asset| activity.asset_url(asset));
+    let start = activity.timestamps.as_ref().and_then(|t| t.start);
+
+    let heading = match activity.kind {
+        Activity::STREAMING => "Streaming",
+        Activity::LISTENING => "Listening to",
+        Activity::WATCHING => "Watching",
+        Activity::COMPETING => "Competing in",
+        Activity::PLAYING => "Playing",
+        // Types Discord adds later read best as a game too.
+        _ => "Playing",
+    };
+
+    html! {
+        <fieldset class="status-activity">
+            <legend>{ heading }</legend>
+            <div class="status-activity-body">
+                if let Some(large_image) = large_image {
+                    <div class="status-activity-assets">
+                        <img
+                            class="status-large-image"
+                            src={large_image}
+                            title={assets.large_text.clone()}
+                            alt="Activity image"
+                        />
+                        if let Some(small_image) = small_image {
+                            <img
+                                class="status-small-image"
+                                src={small_image}
+                                title={assets.small_text.clone()}
+                                alt="Activity icon"
+                            />
+                        }
+                    </div>
+                }
+                <div>
+                    <p><b>{ activity.name.clone() }</b></p>
+                    if let Some(details) = &activity.details {
+                        <p>{ details.clone() }</p>
+                    }
+                    if let Some(state) = &activity.state {
+                        <p>{ state.clone() }</p>
+                    }
+                    if let Some(start) = start {
+                        <Elapsed {start} />
+                    }
+                </div>
+            </div>
+        </fieldset>
+    }
+}
+
+#[function_component(Status)]
+pub fn status() -> Html {
+    let presence = use_state(|| None::<Presence>);
+
+    {
+        let presence = presence.clone();
+        use_effect_with_deps(
+            move |_| {
+                let on_presence = Callback::from(move |p| presence.set(Some(p)));
+                let connection = lanyard::connect(DISCORD_USER_ID, on_presence);
+                move || drop(connection)
+            },
+            (),
+        );
+    }
+
+    let presence = match &*presence {
+        Some(presence) => presence,
+        None => {
+            return html! {
+                <p>{ "Connecting to Discord..." }</p>
+            }
+        }
+    };
+
+    let user = &presence.discord_user;
+    let custom_status = presence
+        .activities
+        .iter()
+        .find(|a| a.kind == Activity::CUSTOM_STATUS);
+
+    html! {
+        <>
+            <div class="status-user">
+                <div class="status-avatar">
+                    if let Some(avatar) = user.avatar_url() {
+                        <img src={avatar} alt="Discord avatar" />
+                    }
+                    <span class={classes!("status-indicator", presence.discord_status.clone())}></span>
+                </div>
+                <div>
+                    <p><b>{ user.display_name().to_string() }</b></p>
+                    <p>{ status_label(&presence.discord_status) }</p>
+                    if let Some(custom_status) = custom_status {
+                        <p class="status-custom">
+                            if let Some(emoji) = &custom_status.emoji {
+                                if let Some(url) = emoji.image_url() {
+                                    <img class="status-emoji" src={url} alt={emoji.name.clone()} />
+                                } else {
+                                    { emoji.name.clone() }{ " " }
+                                }
+                            }
+                            { custom_status.state.clone().unwrap_or_default() }
+                        </p>
+                    }
+                </div>
+            </div>
+            {
+                presence.activities.iter()
+                    .enumerate()
+                    .filter(|(_, a)| a.kind != Activity::CUSTOM_STATUS)
+                    .map(|(index, activity)| html! {
+                        <ActivityCard
+                            key={index}
+                            activity={activity.clone()}
+                        />
+                    })
+                    .collect::<Html>()
+            }
+        </>
+    }
+}
@@ -1,9 +1,8 @@
-use js_sys::Date;
-use wasm_bindgen::JsValue;
 use web_sys::{Element, HtmlTextAreaElement};
 use yew::{function_component, html, use_node_ref, use_state, Callback, Properties};
 
 use crate::copland::Copland;
+use crate::time_format::RelativeTime;
 
 #[derive(Properties, PartialEq, Eq)]
 pub struct StickyNoteProps {
@@ -14,9 +13,6 @@ pub struct StickyNoteProps {
 
 #[function_component(StickyNote)]
 pub fn sticky_note(props: &StickyNoteProps) -> Html {
-    let created_at = Date::new(&JsValue::from((props.created_at * 1_000) as f64))
-        .to_locale_string("en-GB", &JsValue::UNDEFINED);
-
     let textarea = use_node_ref();
     let height = use_state(|| 5);
     let content = use_state(|| props.content.clone());
@@ -58,7 +54,7 @@ pub fn sticky_note(props: &StickyNoteProps) -> Html {
                 value={(*content).clone()}
             ></textarea>
             <div class="status-bar">
-                <p class="status-bar-field">{ created_at }</p>
+                <p class="status-bar-field"><RelativeTime timestamp={props.created_at} /></p>
             </div>
         </>
     }
@@ -0,0 +1,106 @@
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+use gloo::utils::window as browser_window;
+use js_sys::Date;
+use wasm_bindgen::prelude::Closure;
+use wasm_bindgen::JsCast;
+use web_sys::HtmlElement;
+use yew::{function_component, html, use_effect_with_deps, use_node_ref, NodeRef, Properties};
+
+/// An animation frame callback that can re-register itself.
+type FrameCallback = Rc<RefCell<Option<Closure<dyn FnMut()>>>>;
+
+fn format_time(t: u64) -> String {
+    let mins = t / 60;
+    let seconds = t % 60;
+    format!("{}:{:02}", mins, seconds)
+}
+
+#[derive(Properties, PartialEq, Eq)]
+pub struct TrackProgressProps {
+    /// Unix milliseconds the track started playing.
+    pub start_time: u64,
+    /// Unix milliseconds the track will finish.
+    pub end_time: u64,
+}
+
+/// Writes the current position straight into the DOM so the surrounding
+/// component doesn't have to re-render every frame.
+fn draw(fill: &NodeRef, label: &NodeRef, start_time: u64, end_time: u64) {
+    let total = end_time.saturating_sub(start_time).max(1);
+    let elapsed = (Date::now() as u64).saturating_sub(start_time).min(total);
+
+    if let Some(fill) = fill.cast::<HtmlElement>() {
+        let percent = elapsed as f64 / total as f64 * 100.0;
+        fill.style()
+            .set_property("width", &format!("{:.2}%", percent))
+            .ok();
+    }
+    if let Some(label) = label.cast::<HtmlElement>() {
+        let text = format!(
+            "Elapsed: {} / {}",
+            format_time(elapsed / 1000),
+            format_time(total / 1000)
+        );
+        if label.text_content().as_deref() != Some(text.as_str()) {
+            label.set_text_content(Some(&text));
+        }
+    }
+}
+
+/// A Win98 style progress bar for the currently playing track, animated with
+/// `requestAnimationFrame`.
+#[function_component(TrackProgress)]
+pub fn track_progress(props: &TrackProgressProps) -> Html {
+    let fill = use_node_ref();
+    let label = use_node_ref();
+
+    {
+        let fill = fill.clone();
+        let label = label.clone();
+        use_effect_with_deps(
+            move |&(start_time, end_time)| {
+                let frame_id = Rc::new(Cell::new(0));
+                let callback: FrameCallback = Rc::new(RefCell::new(None));
+
+                let next_frame_id = frame_id.clone();
+                let next_callback = callback.clone();
+                *callback.borrow_mut() = Some(Closure::wrap(Box::new(move || {
+                    draw(&fill, &label, start_time, end_time);
+                    if let Some(callback) = next_callback.borrow().as_ref() {
+                        let id = browser_window()
+                            .request_animation_frame(callback.as_ref().unchecked_ref())
+                            .unwrap_or_default();
+                        next_frame_id.set(id);
+                    }
+                }) as Box<dyn FnMut()>));
+
+                if let Some(callback) = callback.borrow().as_ref() {
+                    let id = browser_window()
+                        .request_animation_frame(callback.as_ref().unchecked_ref())
+                        .unwrap_or_default();
+                    frame_id.set(id);
+                }
+
+                move || {
+                    browser_window()
+                        .cancel_animation_frame(frame_id.get())
+                        .ok();
+                    // Breaks the closure's reference to itself so it can be freed.
+                    callback.borrow_mut().take();
+                }
+            },
+            (props.start_time, props.end_time),
+        );
+    }
+
+    html! {
+        <div class="track-progress">
+            <div class="progress-bar">
+                <div class="progress-bar-fill" ref={fill}></div>
+            </div>
+            <p id="spotify-song-duration" ref={label}></p>
+        </div>
+    }
+}
@@ -0,0 +1,210 @@
+//! Builds `assets/backgrounds/manifest.json`, the catalogue of desktop
+//! backgrounds, with a poster frame, a full-size still and average colour
+//! for each one.
+//!
+//! Backgrounds are numbered files such as `7.webm`, `7.mp4` and `7.gif`.
+//! Names and authors are kept from the existing manifest; everything else is
+//! worked out from the files on disk. Posters come from a hand-made still
+//! such as `7.png` if there is one, otherwise the first frame of the GIF, or
+//! of the video by way of `ffmpeg`. A background with none of these gets a
+//! warning and goes without a poster or colour.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{self, File};
+use std::io::{self, BufWriter};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use image::codecs::jpeg::JpegEncoder;
+use image::imageops::FilterType;
+use image::DynamicImage;
+use serde::{Deserialize, Serialize};
+
+use crate::variants::{invalid, is_fresh};
+
+const POSTERS_DIR: &str = "posters";
+const POSTER_WIDTH: u32 = 320;
+/// Full-size first frames, for the desktop when the video is paused.
+const STILLS_DIR: &str = "stills";
+/// In the order browsers should try them.
+const FORMATS: [&str; 3] = ["webm", "mp4", "gif"];
+
+#[derive(Serialize, Deserialize, Debug)]
+struct Background {
+    id: u32,
+    name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    author: Option<String>,
+    formats: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    poster: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    still: Option<String>,
+    /// "#rrggbb", shown behind the video while it loads.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    colour: Option<String>,
+}
+
+fn average_colour(image: &DynamicImage) -> String {
+    let small = image.resize(64, 64, FilterType::Triangle).to_rgb8();
+    let count = small.pixels().len().max(1) as u64;
+    let sum = small.pixels().fold([0u64; 3], |mut sum, p| {
+        sum.iter_mut().zip(p.0).for_each(|(s, c)| *s += c as u64);
+        sum
+    });
+    format!(
+        "#{:02x}{:02x}{:02x}",
+        sum[0] / count,
+        sum[1] / count,
+        sum[2] / count
+    )
+}
+
+/// The first frame of `video`, read with `ffmpeg` if it's installed.
+fn video_frame(video: &Path) -> Option<DynamicImage> {
+    let output = Command::new("ffmpeg")
+        .args(["-v", "error", "-i"])
+        .arg(video)
+        .args(["-frames:v", "1", "-f", "image2pipe", "-c:v", "png", "-"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        eprintln!(
+            "ffmpeg couldn't read {}: {}",
+            video.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+        return None;
+    }
+    image::load_from_memory(&output.stdout).ok()
+}
+
+/// The frame to make `id`'s poster from, and the file it came from, if
+/// there's a way to get one.
+fn first_frame(
+    dir: &Path,
+    id: u32,
+    formats: &[String],
+) -> io::Result<Option<(PathBuf, DynamicImage)>> {
+    let still = dir.join(format!("{}.png", id));
+    if still.exists() {
+        let frame = image::open(&still).map_err(invalid)?;
+        return Ok(Some((still, frame)));
+    }
+    // GIFs first, since they don't need ffmpeg.
+    for format in formats.iter().rev() {
+        let source = dir.join(format!("{}.{}", id, format));
+        let frame = if format == "gif" {
+            Some(image::open(&source).map_err(invalid)?)
+        } else {
+            video_frame(&source)
+        };
+        if let Some(frame) = frame {
+            return Ok(Some((source, frame)));
+        }
+    }
+    eprintln!(
+        "warning: background {} has no poster: install ffmpeg or add a still as {}",
+        id,
+        still.display()
+    );
+    Ok(None)
+}
+
+/// Writes `frame` to `sub_dir` as a JPEG no wider than `width`, unless it's
+/// newer than `source` already, returning its path relative to `dir`.
+fn write_frame(
+    dir: &Path,
+    sub_dir: &str,
+    id: u32,
+    source: &Path,
+    frame: &DynamicImage,
+    width: u32,
+) -> io::Result<String> {
+    let name = format!("{}/{}.jpg", sub_dir, id);
+    let output = dir.join(&name);
+    if !is_fresh(source, &output) {
+        fs::create_dir_all(dir.join(sub_dir))?;
+        let resized = frame.resize(width.min(frame.width()), u32::MAX, FilterType::Lanczos3);
+        let writer = BufWriter::new(File::create(&output)?);
+        JpegEncoder::new_with_quality(writer, 80)
+            .encode_image(&resized.to_rgb8())
+            .map_err(invalid)?;
+        println!("wrote {}", output.display());
+    }
+    Ok(name)
+}
+
+/// A background's poster, full-size still and average colour.
+struct Frames {
+    poster: String,
+    still: String,
+    colour: String,
+}
+
+fn frames(dir: &Path, id: u32, formats: &[String]) -> io::Result<Option<Frames>> {
+    let Some((source, frame)) = first_frame(dir, id, formats)? else {
+        return Ok(None);
+    };
+    Ok(Some(Frames {
+        poster: write_frame(dir, POSTERS_DIR, id, &source, &frame, POSTER_WIDTH)?,
+        still: write_frame(dir, STILLS_DIR, id, &source, &frame, u32::MAX)?,
+        colour: average_colour(&frame),
+    }))
+}
+
+pub fn generate(dir: &Path) -> io::Result<()> {
+    let manifest_path = dir.join(super::MANIFEST);
+    let mut known: HashMap<u32, Background> = match fs::read_to_string(&manifest_path) {
+        Ok(existing) => serde_json::from_str::<Vec<Background>>(&existing)
+            .map_err(invalid)?
+            .into_iter()
+            .map(|b| (b.id, b))
+            .collect(),
+        Err(_) => HashMap::new(),
+    };
+
+    // Background number to the formats it comes in.
+    let mut found: BTreeMap<u32, Vec<String>> = BTreeMap::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let (Some(stem), Some(extension)) = (
+            path.file_stem().and_then(|s| s.to_str()),
+            path.extension().and_then(|e| e.to_str()),
+        ) else {
+            continue;
+        };
+        if let (Ok(id), true) = (stem.parse::<u32>(), FORMATS.contains(&extension)) {
+            found.entry(id).or_default().push(extension.to_string());
+        }
+    }
+
+    let mut backgrounds = Vec::new();
+    for (id, mut formats) in found {
+        formats.sort_by_key(|f| FORMATS.iter().position(|known| known == f));
+        let frames = frames(dir, id, &formats)?;
+
+        let (name, author) = match known.remove(&id) {
+            Some(existing) => (existing.name, existing.author),
+            None => {
+                println!("added background {}", id);
+                (format!("Background {}", id), None)
+            }
+        };
+        backgrounds.push(Background {
+            id,
+            name,
+            author,
+            formats,
+            poster: frames.as_ref().map(|f| f.poster.clone()),
+            still: frames.as_ref().map(|f| f.still.clone()),
+            colour: frames.map(|f| f.colour),
+        });
+    }
+    for id in known.keys() {
+        println!("removed background {} (files not found)", id);
+    }
+
+    let json = serde_json::to_string_pretty(&backgrounds).map_err(invalid)?;
+    fs::write(manifest_path, json + "\n")
+}
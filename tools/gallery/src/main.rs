@@ -0,0 +1,168 @@
+//! Builds `assets/photo_gallery/manifest.json` from the gallery directory,
+//! then generates resized variants of the gallery and the project splashes
+//! and catalogues the desktop backgrounds.
+//!
+//! Every sub-directory becomes an album. Titles, captions and the rest of the
+//! hand-written metadata are kept from the existing manifest; new files get a
+//! title from their file name and entries whose files have gone are dropped.
+
+mod backgrounds;
+mod variants;
+
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+const GALLERY_DIR: &str = "assets/photo_gallery";
+const SPLASHES_DIR: &str = "assets/project_splashes";
+const BACKGROUNDS_DIR: &str = "assets/backgrounds";
+const MANIFEST: &str = "manifest.json";
+const EXTENSIONS: [&str; 5] = ["jpg", "jpeg", "png", "gif", "webp"];
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+struct Photo {
+    file: String,
+    title: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    caption: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    date: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    camera: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    film_stock: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct Album {
+    name: String,
+    #[serde(default)]
+    photos: Vec<Photo>,
+    #[serde(default)]
+    albums: Vec<Album>,
+}
+
+impl Album {
+    fn files(&self, files: &mut Vec<String>) {
+        files.extend(self.photos.iter().map(|p| p.file.clone()));
+        for album in &self.albums {
+            album.files(files);
+        }
+    }
+
+    fn into_photos(self, photos: &mut HashMap<String, Photo>) {
+        for photo in self.photos {
+            photos.insert(photo.file.clone(), photo);
+        }
+        for album in self.albums {
+            album.into_photos(photos);
+        }
+    }
+}
+
+fn is_image(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| EXTENSIONS.contains(&e.to_ascii_lowercase().as_str()))
+}
+
+/// "000101080016" stays as-is, "my_cool-photo" becomes "my cool photo".
+fn title_from_file(path: &Path) -> String {
+    path.file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or_default()
+        .replace(['_', '-'], " ")
+}
+
+/// Path relative to the gallery directory with forward slashes, as used in URLs.
+fn relative(root: &Path, path: &Path) -> String {
+    path.strip_prefix(root)
+        .unwrap_or(path)
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn scan(
+    root: &Path,
+    dir: &Path,
+    name: String,
+    known: &mut HashMap<String, Photo>,
+) -> io::Result<Album> {
+    let mut entries: Vec<PathBuf> = fs::read_dir(dir)?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<io::Result<_>>()?;
+    entries.sort();
+
+    let mut album = Album {
+        name,
+        photos: Vec::new(),
+        albums: Vec::new(),
+    };
+    for path in entries {
+        let file_name = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or_default();
+        if file_name.starts_with('.') || file_name == variants::VARIANTS_DIR {
+            continue;
+        }
+        if path.is_dir() {
+            let child = scan(root, &path, file_name.to_string(), known)?;
+            if !child.photos.is_empty() || !child.albums.is_empty() {
+                album.albums.push(child);
+            }
+        } else if is_image(&path) {
+            let file = relative(root, &path);
+            let photo = known.remove(&file).unwrap_or_else(|| {
+                println!("added {}", file);
+                Photo {
+                    file,
+                    title: title_from_file(&path),
+                    caption: None,
+                    date: None,
+                    camera: None,
+                    film_stock: None,
+                }
+            });
+            album.photos.push(photo);
+        }
+    }
+    Ok(album)
+}
+
+fn main() -> io::Result<()> {
+    let root = Path::new(GALLERY_DIR);
+    let manifest_path = root.join(MANIFEST);
+
+    let mut known = HashMap::new();
+    let mut name = "photos".to_string();
+    if let Ok(existing) = fs::read_to_string(&manifest_path) {
+        let album: Album = serde_json::from_str(&existing)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        name = album.name.clone();
+        album.into_photos(&mut known);
+    }
+
+    let album = scan(root, root, name, &mut known)?;
+    for file in known.keys() {
+        println!("removed {} (file not found)", file);
+    }
+
+    let json = serde_json::to_string_pretty(&album)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    fs::write(&manifest_path, json + "\n")?;
+
+    let mut files = Vec::new();
+    album.files(&mut files);
+    variants::generate(root, &files)?;
+
+    let splashes = Path::new(SPLASHES_DIR);
+    variants::generate(splashes, &variants::images_in(splashes)?)?;
+
+    backgrounds::generate(Path::new(BACKGROUNDS_DIR))
+}
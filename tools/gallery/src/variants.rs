@@ -0,0 +1,168 @@
+//! Resized WebP, AVIF and JPEG copies of every image in a directory.
+//!
+//! Everything goes in a `variants` sub-directory alongside a `manifest.json`
+//! that maps each original file to its dimensions, a tiny blurred placeholder
+//! and the generated files, so the site can build `srcset`s from it.
+
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{self, BufWriter};
+use std::path::Path;
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use image::codecs::avif::AvifEncoder;
+use image::codecs::jpeg::JpegEncoder;
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageEncoder, ImageReader};
+use serde::Serialize;
+
+pub const VARIANTS_DIR: &str = "variants";
+const WIDTHS: [u32; 3] = [160, 480, 1280];
+const PLACEHOLDER_WIDTH: u32 = 16;
+const JPEG_QUALITY: u8 = 80;
+const WEBP_QUALITY: f32 = 75.0;
+const AVIF_QUALITY: u8 = 60;
+const AVIF_SPEED: u8 = 8;
+
+#[derive(Serialize)]
+struct Variant {
+    file: String,
+    width: u32,
+    format: &'static str,
+}
+
+#[derive(Serialize)]
+struct ImageInfo {
+    width: u32,
+    height: u32,
+    placeholder: String,
+    variants: Vec<Variant>,
+}
+
+pub fn invalid(e: impl std::error::Error + Send + Sync + 'static) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, e)
+}
+
+/// Skips work when the variant is already newer than its source.
+pub fn is_fresh(source: &Path, output: &Path) -> bool {
+    let modified = |path: &Path| fs::metadata(path).and_then(|m| m.modified()).ok();
+    match (modified(source), modified(output)) {
+        (Some(source), Some(output)) => output >= source,
+        _ => false,
+    }
+}
+
+fn write_variant(image: &DynamicImage, format: &str, path: &Path) -> io::Result<()> {
+    match format {
+        "jpeg" => {
+            let writer = BufWriter::new(File::create(path)?);
+            JpegEncoder::new_with_quality(writer, JPEG_QUALITY)
+                .encode_image(&image.to_rgb8())
+                .map_err(invalid)
+        }
+        "webp" => {
+            let rgba = image.to_rgba8();
+            let encoded =
+                webp::Encoder::from_rgba(&rgba, rgba.width(), rgba.height()).encode(WEBP_QUALITY);
+            fs::write(path, &*encoded)
+        }
+        "avif" => {
+            let rgba = image.to_rgba8();
+            let writer = BufWriter::new(File::create(path)?);
+            AvifEncoder::new_with_speed_quality(writer, AVIF_SPEED, AVIF_QUALITY)
+                .write_image(
+                    &rgba,
+                    rgba.width(),
+                    rgba.height(),
+                    image::ExtendedColorType::Rgba8,
+                )
+                .map_err(invalid)
+        }
+        _ => unreachable!("unknown format {}", format),
+    }
+}
+
+/// A few hundred bytes of JPEG as a data URL, shown blurred while loading.
+fn placeholder(image: &DynamicImage) -> io::Result<String> {
+    let small = image.resize(PLACEHOLDER_WIDTH, u32::MAX, FilterType::Triangle);
+    let mut bytes = Vec::new();
+    JpegEncoder::new_with_quality(&mut bytes, 50)
+        .encode_image(&small.to_rgb8())
+        .map_err(invalid)?;
+    Ok(format!("data:image/jpeg;base64,{}", BASE64.encode(bytes)))
+}
+
+fn process(dir: &Path, file: &str) -> io::Result<ImageInfo> {
+    let source = dir.join(file);
+    let image = ImageReader::open(&source)?
+        .with_guessed_format()?
+        .decode()
+        .map_err(invalid)?;
+    let (width, height) = (image.width(), image.height());
+
+    // Never upscale; images narrower than every size just get one copy.
+    let mut widths: Vec<u32> = WIDTHS.iter().copied().filter(|w| *w < width).collect();
+    if widths.is_empty() {
+        widths.push(width);
+    }
+
+    let stem = file.rsplit_once('.').map_or(file, |(stem, _)| stem);
+    let mut variants = Vec::new();
+    for variant_width in widths {
+        let mut resized = None;
+        for format in ["avif", "webp", "jpeg"] {
+            let name = format!("{}/{}-{}.{}", VARIANTS_DIR, stem, variant_width, format);
+            let output = dir.join(&name);
+            if !is_fresh(&source, &output) {
+                if let Some(parent) = output.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                let resized = resized.get_or_insert_with(|| {
+                    image.resize(variant_width, u32::MAX, FilterType::Lanczos3)
+                });
+                write_variant(resized, format, &output)?;
+                println!("wrote {}", output.display());
+            }
+            variants.push(Variant {
+                file: name,
+                width: variant_width,
+                format,
+            });
+        }
+    }
+
+    Ok(ImageInfo {
+        width,
+        height,
+        placeholder: placeholder(&image)?,
+        variants,
+    })
+}
+
+/// Generates variants for `files`, given relative to `dir`, and writes the
+/// variants manifest.
+pub fn generate(dir: &Path, files: &[String]) -> io::Result<()> {
+    let mut manifest = BTreeMap::new();
+    for file in files {
+        manifest.insert(file.clone(), process(dir, file)?);
+    }
+
+    let json = serde_json::to_string_pretty(&manifest).map_err(invalid)?;
+    let variants_dir = dir.join(VARIANTS_DIR);
+    fs::create_dir_all(&variants_dir)?;
+    fs::write(variants_dir.join("manifest.json"), json + "\n")
+}
+
+/// Every image directly inside `dir`, for folders without a gallery manifest.
+pub fn images_in(dir: &Path) -> io::Result<Vec<String>> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_file() && super::is_image(&path) {
+            files.push(super::relative(dir, &path));
+        }
+    }
+    files.sort();
+    Ok(files)
+}